@@ -1,22 +1,314 @@
-use chrono::{DateTime, Utc};
+use crate::error::UpdateError;
+use crate::notify::{Notifier, StderrNotifier};
+use crate::source::{VersionSnapshot, VersionSource};
+use crate::store::{CacheStore, FileCacheStore};
+use chrono::{DateTime, NaiveDateTime, Utc};
 use humanly::{HumanDuration, HumanTime};
 use regex::Regex;
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::fs;
-use std::path::PathBuf;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use reqwest::StatusCode;
+use reqwest::redirect::Policy;
+use semver::{Version, VersionReq};
+use serde::de::{DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Serialize, Serializer};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::io::{BufReader, Read};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex, OnceLock, PoisonError, mpsc};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 /// Time in seconds before cache entries expire (1 hour).
 const CACHE_EXPIRE_TIME: u64 = 3600;
 
+/// Time in seconds before cached "crate not found" entries and transient
+/// failures expire (5 minutes).
+const NOT_FOUND_EXPIRE_TIME: u64 = 300;
+
+/// Default number of checks run in parallel by batch methods.
+const DEFAULT_CONCURRENCY: usize = 4;
+
+/// Default timeout for registry requests.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Base URL of the crates.io API endpoint listing a crate's versions.
+const CRATES_IO_API_URL: &str = "https://crates.io/api/v1/crates";
+
+/// Version in the cache key of first-run markers (see
+/// [`UpdateChecker::with_initial_grace`]). It's never a valid version, so
+/// markers can't collide with check results.
+const FIRST_RUN_MARKER: &str = "";
+
+/// Default number of redirects followed by registry requests.
+const DEFAULT_MAX_REDIRECTS: usize = 5;
+
+/// Default number of connections opened to the registry at once.
+const DEFAULT_MAX_CONNECTIONS_PER_HOST: usize = DEFAULT_CONCURRENCY;
+
+/// Callback invoked after every registry request with the URL, elapsed time,
+/// and the response status (or `Err(())` if no response was received).
+type RequestObserver = Arc<dyn Fn(&str, Duration, Result<StatusCode, ()>) + Send + Sync>;
+
+/// Callback invoked when the persistent cache is loaded, created, or discarded.
+type CacheObserver = Arc<dyn Fn(&CacheEvent) + Send + Sync>;
+
+/// Seconds a release date may lie in the future before it's treated as genuinely
+/// future rather than clock skew between the local machine and the registry.
+const CLOCK_SKEW_TOLERANCE: i64 = 300;
+
+/// Pattern splitting a version string into its comparable components.
+const VERSION_COMPONENT_PATTERN: &str = r"(\d+|[a-z]+|\.|-)";
+
+/// Compiled [`VERSION_COMPONENT_PATTERN`], shared across all `parse_version` calls.
+static VERSION_COMPONENT_RE: OnceLock<Regex> = OnceLock::new();
+
+/// Built-in prerelease tags and their ranks. Lower ranks are older releases.
+const BUILTIN_PRERELEASE_TAGS: [(&str, u8); 12] = [
+    ("dev", 10),
+    ("nightly", 10),
+    ("snapshot", 10),
+    ("a", 20),
+    ("alpha", 20),
+    ("b", 30),
+    ("beta", 30),
+    ("milestone", 35),
+    ("c", 40),
+    ("rc", 40),
+    ("pre", 40),
+    ("preview", 40),
+];
+
+/// Tags marking a post-release (PEP 440 style) of the version they follow, for
+/// crates with post-release semantics enabled.
+const POST_RELEASE_TAGS: [&str; 3] = ["post", "rev", "r"];
+
+/// The built-in [`PrereleaseTags`], used by [`parse_version`].
+static DEFAULT_PRERELEASE_TAGS: OnceLock<PrereleaseTags> = OnceLock::new();
+
+/// Ranks of recognised prerelease tags, used to order versions.
+///
+/// Every ranked tag orders before the final release, and tags order amongst
+/// themselves by rank.
+#[derive(Debug, Clone)]
+pub(crate) struct PrereleaseTags {
+    /// Rank of each tag, keyed by lowercase tag
+    ranks: HashMap<String, u8>,
+    /// Whether [`POST_RELEASE_TAGS`] mark post-releases
+    pub(crate) post_releases: bool,
+}
+
+impl PrereleaseTags {
+    /// Adds or replaces the rank of a tag.
+    pub(crate) fn insert(&mut self, tag: &str, rank: u8) {
+        self.ranks.insert(tag.to_lowercase(), rank);
+    }
+
+    /// Returns the rank of a lowercase tag, if it is recognised.
+    fn rank(&self, tag: &str) -> Option<u8> {
+        self.ranks.get(tag).copied()
+    }
+
+    /// Returns `true` if a lowercase tag marks a post-release.
+    fn is_post_release(&self, tag: &str) -> bool {
+        self.post_releases && POST_RELEASE_TAGS.contains(&tag)
+    }
+
+    /// Returns `true` if a version is a release rather than a prerelease.
+    ///
+    /// This is [`standard_release`], but with post-release semantics enabled,
+    /// post-releases of a standard release (e.g. `1.0.0-post1`) count too.
+    pub(crate) fn is_release(&self, version: &str) -> bool {
+        if standard_release(version) {
+            return true;
+        }
+        if !self.post_releases {
+            return false;
+        }
+
+        let component_re =
+            VERSION_COMPONENT_RE.get_or_init(|| Regex::new(VERSION_COMPONENT_PATTERN).unwrap());
+        component_re.find_iter(&version.to_lowercase()).all(|part| {
+            let part = part.as_str();
+            part == "."
+                || part == "-"
+                || part.starts_with(|c: char| c.is_ascii_digit())
+                || self.is_post_release(part)
+        })
+    }
+}
+
+impl Default for PrereleaseTags {
+    fn default() -> Self {
+        PrereleaseTags {
+            ranks: BUILTIN_PRERELEASE_TAGS
+                .iter()
+                .map(|&(tag, rank)| (tag.to_string(), rank))
+                .collect(),
+            post_releases: false,
+        }
+    }
+}
+
 /// A cached entry containing timestamp and optional update result.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-struct CacheEntry {
+pub(crate) struct CacheEntry {
+    /// Unix timestamp when this entry was cached
+    pub(crate) timestamp: u64,
+    /// The update result, if an update was available
+    pub(crate) result: Option<UpdateResult>,
+    /// The error the check failed with, if it failed
+    #[serde(
+        serialize_with = "serialize_cached_error",
+        deserialize_with = "deserialize_cached_error"
+    )]
+    pub(crate) error: Option<UpdateError>,
+}
+
+/// Serialises a cached error as a length-prefixed blob, so a release that
+/// doesn't know its variant can still skip over it.
+fn serialize_cached_error<S: Serializer>(
+    error: &Option<UpdateError>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let data = error
+        .as_ref()
+        .map(postcard::to_allocvec)
+        .transpose()
+        .map_err(serde::ser::Error::custom)?;
+    data.serialize(serializer)
+}
+
+/// Deserialises an error written by [`serialize_cached_error`].
+///
+/// An error this release doesn't recognise, e.g. a variant added by a newer
+/// release sharing the cache, is read as [`UpdateError::Cache`], so the entry
+/// counts as a transient failure and expires soon.
+fn deserialize_cached_error<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<UpdateError>, D::Error> {
+    let data = Option::<Vec<u8>>::deserialize(deserializer)?;
+    Ok(data.map(|data| {
+        postcard::from_bytes(&data)
+            .unwrap_or_else(|_| UpdateError::Cache("Unrecognised cached error".to_string()))
+    }))
+}
+
+impl CacheEntry {
+    /// Returns `true` if this entry hasn't expired yet.
+    ///
+    /// "Not found" entries and transient failures expire sooner than regular
+    /// ones, so a crate that gets published shortly after being checked is
+    /// picked up quickly, and an unreachable registry is retried soon.
+    /// Definitive answers, such as every version being yanked, last as long
+    /// as a successful check.
+    pub(crate) fn is_fresh(&self, now: u64) -> bool {
+        self.remaining(now) > 0
+    }
+
+    /// Returns how many seconds remain before this entry expires.
+    pub(crate) fn remaining(&self, now: u64) -> u64 {
+        let ttl = match self.error {
+            None
+            | Some(UpdateError::AllYanked)
+            | Some(UpdateError::NoSuitableVersion)
+            | Some(UpdateError::InvalidVersion(_)) => CACHE_EXPIRE_TIME,
+            Some(_) => NOT_FOUND_EXPIRE_TIME,
+        };
+
+        ttl.saturating_sub(now.saturating_sub(self.timestamp))
+    }
+}
+
+/// In-memory cache contents, keyed by crate name and running version.
+type CacheEntries = HashMap<(String, String), CacheEntry>;
+
+/// Prefix of serialised cache data, followed by the format version.
+const CACHE_MAGIC: &[u8; 4] = b"UPDC";
+
+/// Version of the cache format written by this release. Caches written in
+/// other versions are discarded.
+const CACHE_FORMAT_VERSION: u8 = 2;
+
+/// A cache entry as written by releases before the cache format was versioned.
+#[derive(Deserialize)]
+struct LegacyCacheEntry {
     /// Unix timestamp when this entry was cached
     timestamp: u64,
     /// The update result, if an update was available
-    result: Option<UpdateResult>,
+    result: Option<LegacyUpdateResult>,
+}
+
+/// An update result as written by releases before the cache format was versioned.
+#[derive(Deserialize)]
+struct LegacyUpdateResult {
+    /// Name of the crate
+    crate_name: String,
+    /// The version currently in use
+    running_version: String,
+    /// The latest available version
+    available_version: String,
+    /// When the latest version was released (if available)
+    #[serde(with = "chrono::serde::ts_seconds_option")]
+    release_date: Option<DateTime<Utc>>,
+}
+
+impl From<LegacyCacheEntry> for CacheEntry {
+    fn from(entry: LegacyCacheEntry) -> Self {
+        CacheEntry {
+            timestamp: entry.timestamp,
+            result: entry.result.map(|result| UpdateResult {
+                crate_name: result.crate_name,
+                running_version: result.running_version,
+                available_version: result.available_version,
+                release_date: result.release_date,
+                running_yanked: false,
+                yank_reason: None,
+            }),
+            error: None,
+        }
+    }
+}
+
+/// Serialises cache entries in the current format, prefixed with its header.
+fn encode_cache(cache: &CacheEntries) -> Result<Vec<u8>, UpdateError> {
+    let mut data = CACHE_MAGIC.to_vec();
+    data.push(CACHE_FORMAT_VERSION);
+    postcard::to_extend(cache, data).map_err(|e| UpdateError::Cache(e.to_string()))
+}
+
+/// Deserialises cache data written by [`encode_cache`] or by an older release.
+///
+/// # Returns
+///
+/// * `Ok((entries, migrated))` - The cache entries, and whether they were
+///   converted from an older format
+/// * `Err(UpdateError::Cache)` - If the data isn't a cache this release can read
+fn decode_cache(data: &[u8]) -> Result<(CacheEntries, bool), UpdateError> {
+    let to_error = |e: postcard::Error| UpdateError::Cache(e.to_string());
+
+    match data.strip_prefix(CACHE_MAGIC.as_slice()) {
+        Some([CACHE_FORMAT_VERSION, entries @ ..]) => {
+            Ok((postcard::from_bytes(entries).map_err(to_error)?, false))
+        }
+        Some(_) => Err(UpdateError::Cache(
+            "Unsupported cache format version".to_string(),
+        )),
+        // Caches written before the header was introduced
+        None => {
+            let (legacy, rest) =
+                postcard::take_from_bytes::<HashMap<(String, String), LegacyCacheEntry>>(data)
+                    .map_err(to_error)?;
+            if !rest.is_empty() {
+                return Err(UpdateError::Cache("Trailing cache data".to_string()));
+            }
+
+            let cache = legacy
+                .into_iter()
+                .map(|(key, entry)| (key, CacheEntry::from(entry)))
+                .collect();
+            Ok((cache, true))
+        }
+    }
 }
 
 /// Information about an available crate update.
@@ -33,6 +325,8 @@ struct CacheEntry {
 ///     running_version: "1.0.150".to_string(),
 ///     available_version: "1.0.200".to_string(),
 ///     release_date: None,
+///     running_yanked: false,
+///     yank_reason: None,
 /// };
 ///
 /// println!("{}", result);
@@ -49,6 +343,16 @@ pub struct UpdateResult {
     /// When the latest version was released (if available)
     #[serde(with = "chrono::serde::ts_seconds_option")]
     pub release_date: Option<DateTime<Utc>>,
+    /// Whether the running version has been yanked.
+    ///
+    /// A yanked running version is reported even when nothing newer is
+    /// published. In that case, `available_version` is the newest version that
+    /// hasn't been yanked, which is older than the running one.
+    #[serde(default)]
+    pub running_yanked: bool,
+    /// Why the running version was yanked, if it was and the registry said why
+    #[serde(default)]
+    pub yank_reason: Option<String>,
 }
 
 impl UpdateResult {
@@ -59,30 +363,263 @@ impl UpdateResult {
     /// * `package` - The name of the crate
     /// * `running` - The current version string
     /// * `available` - The latest available version string
-    /// * `release_date` - Optional RFC3339 timestamp of the release
+    /// * `release_date` - Optional timestamp of the release (see [`parse_release_date`])
+    /// * `running_yanked` - Whether the running version has been yanked
+    /// * `yank_reason` - Why the running version was yanked, if known
     fn new(
         package: String,
         running: String,
         available: String,
         release_date: Option<String>,
+        running_yanked: bool,
+        yank_reason: Option<String>,
     ) -> Self {
-        let parsed_date = release_date.and_then(|d| {
-            DateTime::parse_from_rfc3339(&d)
-                .ok()
-                .map(|dt| dt.with_timezone(&Utc))
-        });
+        let parsed_date = release_date.and_then(|d| parse_release_date(&d));
 
         UpdateResult {
             crate_name: package,
             running_version: running,
             available_version: available,
             release_date: parsed_date,
+            running_yanked,
+            yank_reason,
+        }
+    }
+
+    /// Returns `true` if the available version is itself a prerelease.
+    ///
+    /// Prereleases are only offered when the running version is a prerelease,
+    /// so this lets callers flag such updates distinctly (e.g. "update available
+    /// (prerelease)").
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use updates::UpdateResult;
+    ///
+    /// let result = UpdateResult {
+    ///     crate_name: "tokio".to_string(),
+    ///     running_version: "1.0.0-alpha.1".to_string(),
+    ///     available_version: "1.0.0-beta.1".to_string(),
+    ///     release_date: None,
+    ///     running_yanked: false,
+    ///     yank_reason: None,
+    /// };
+    ///
+    /// assert!(result.available_is_prerelease());
+    /// ```
+    pub fn available_is_prerelease(&self) -> bool {
+        !standard_release(&self.available_version)
+    }
+
+    /// Returns a compact, single-line summary of the update without the release
+    /// date, e.g. `serde 1.0.150 → 1.0.200`.
+    ///
+    /// Use this for status lines; the `Display` output is the full notice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use updates::UpdateResult;
+    ///
+    /// let result = UpdateResult {
+    ///     crate_name: "serde".to_string(),
+    ///     running_version: "1.0.150".to_string(),
+    ///     available_version: "1.0.200".to_string(),
+    ///     release_date: None,
+    ///     running_yanked: false,
+    ///     yank_reason: None,
+    /// };
+    ///
+    /// assert_eq!(result.short(), "serde 1.0.150 → 1.0.200");
+    /// ```
+    pub fn short(&self) -> String {
+        format!(
+            "{} {} → {}",
+            self.crate_name, self.running_version, self.available_version
+        )
+    }
+
+    /// Returns how many major versions the available version is ahead of the
+    /// running one.
+    ///
+    /// A large jump (e.g. running `1.x` while `4.x` is available) often means a
+    /// dependency has been left behind for a long time, so callers may want to
+    /// escalate it beyond the normal notice.
+    ///
+    /// # Returns
+    ///
+    /// The difference in major versions, or `0` if the available version isn't
+    /// a newer major or either version isn't valid semver.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use updates::UpdateResult;
+    ///
+    /// let result = UpdateResult {
+    ///     crate_name: "my-tool".to_string(),
+    ///     running_version: "1.4.0".to_string(),
+    ///     available_version: "4.0.1".to_string(),
+    ///     release_date: None,
+    ///     running_yanked: false,
+    ///     yank_reason: None,
+    /// };
+    ///
+    /// if result.major_jump() >= 2 {
+    ///     eprintln!("{} is {} major versions behind", result.crate_name, result.major_jump());
+    /// }
+    /// ```
+    pub fn major_jump(&self) -> u64 {
+        match (
+            Version::parse(&self.running_version),
+            Version::parse(&self.available_version),
+        ) {
+            (Ok(running), Ok(available)) => available.major.saturating_sub(running.major),
+            _ => 0,
+        }
+    }
+
+    /// Returns the Cargo command that updates a dependency to the available
+    /// version.
+    ///
+    /// If the available version is semver-compatible with the running one, the
+    /// lockfile only needs updating (`cargo update -p serde`). Otherwise, the
+    /// requirement in `Cargo.toml` has to change too (`cargo add serde@2.0.0`).
+    ///
+    /// For binaries installed with `cargo install`, use
+    /// [`suggested_install_command`](Self::suggested_install_command) instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use updates::UpdateResult;
+    ///
+    /// let result = UpdateResult {
+    ///     crate_name: "serde".to_string(),
+    ///     running_version: "1.0.150".to_string(),
+    ///     available_version: "1.0.200".to_string(),
+    ///     release_date: None,
+    ///     running_yanked: false,
+    ///     yank_reason: None,
+    /// };
+    ///
+    /// assert_eq!(result.suggested_command(), "cargo update -p serde");
+    /// ```
+    pub fn suggested_command(&self) -> String {
+        let compatible = VersionReq::parse(&format!("^{}", self.running_version))
+            .ok()
+            .zip(Version::parse(&self.available_version).ok())
+            .is_none_or(|(req, available)| req.matches(&available));
+
+        if compatible {
+            format!("cargo update -p {}", self.crate_name)
+        } else {
+            format!("cargo add {}@{}", self.crate_name, self.available_version)
         }
     }
+
+    /// Returns the Cargo command that installs the available version of a
+    /// binary crate, e.g. `cargo install ripgrep@14.1.0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use updates::UpdateResult;
+    ///
+    /// let result = UpdateResult {
+    ///     crate_name: "ripgrep".to_string(),
+    ///     running_version: "13.0.0".to_string(),
+    ///     available_version: "14.1.0".to_string(),
+    ///     release_date: None,
+    ///     running_yanked: false,
+    ///     yank_reason: None,
+    /// };
+    ///
+    /// eprintln!("{}\nTo update, run: {}", result, result.suggested_install_command());
+    /// ```
+    pub fn suggested_install_command(&self) -> String {
+        format!(
+            "cargo install {}@{}",
+            self.crate_name, self.available_version
+        )
+    }
+
+    /// Returns `true` if the registry reported when the available version was
+    /// released.
+    ///
+    /// When this is `false`, the registry either omitted the `created_at`
+    /// timestamp or returned one that couldn't be parsed, and age-based
+    /// information about the release is unavailable.
+    pub fn has_release_date(&self) -> bool {
+        self.release_date.is_some()
+    }
+
+    /// Returns how many whole days ago the available version was released.
+    ///
+    /// This is the numeric counterpart of the relative date shown by `Display`,
+    /// useful for sorting results by staleness or applying thresholds such as
+    /// "warn if older than 90 days".
+    ///
+    /// # Returns
+    ///
+    /// * `Some(i64)` - Days since release (negative if the date is in the future)
+    /// * `None` - If the release date is unknown
+    pub fn days_since_release(&self) -> Option<i64> {
+        self.release_date
+            .map(|date| Utc::now().signed_duration_since(date).num_days())
+    }
+}
+
+impl UpdateResult {
+    /// Returns the fields compared by `PartialEq` and `Hash`.
+    ///
+    /// The release date is compared by its Unix seconds, since that's all the
+    /// cache keeps, so a result read back from the cache equals the original.
+    fn identity(&self) -> (&str, &str, &str, Option<i64>, bool, Option<&str>) {
+        (
+            &self.crate_name,
+            &self.running_version,
+            &self.available_version,
+            self.release_date.map(|date| date.timestamp()),
+            self.running_yanked,
+            self.yank_reason.as_deref(),
+        )
+    }
+}
+
+impl PartialEq for UpdateResult {
+    fn eq(&self, other: &Self) -> bool {
+        self.identity() == other.identity()
+    }
+}
+
+impl Eq for UpdateResult {}
+
+impl std::hash::Hash for UpdateResult {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.identity().hash(state);
+    }
 }
 
 impl std::fmt::Display for UpdateResult {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.running_yanked {
+            write!(
+                f,
+                "Version {} of {} has been yanked",
+                self.running_version, self.crate_name
+            )?;
+            match &self.yank_reason {
+                Some(reason) => write!(f, ": {}. ", reason.trim_end_matches('.'))?,
+                None => write!(f, ". ")?,
+            }
+
+            if parse_version(&self.running_version) >= parse_version(&self.available_version) {
+                return write!(f, "No newer version is available.");
+            }
+        }
+
         write!(
             f,
             "Version {} of {} is outdated. Version {} ",
@@ -97,266 +634,3037 @@ impl std::fmt::Display for UpdateResult {
     }
 }
 
-/// Response structure from crates.io API.
-#[derive(Deserialize)]
-struct CratesIoResponse {
-    /// List of all versions for the crate
-    versions: Vec<VersionInfo>,
+/// The smallest version change that [`UpdateChecker`] reports as an update.
+///
+/// See [`UpdateChecker::notify_granularity`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Granularity {
+    /// Report every newer version, including patch releases
+    #[default]
+    Patch,
+    /// Report only versions with a newer major or minor component
+    Minor,
+    /// Report only versions with a newer major component
+    Major,
+}
+
+impl Granularity {
+    /// Returns `true` if the change from `running` to `available` is at least
+    /// as large as this granularity.
+    ///
+    /// Versions that aren't valid semver can't be compared by component, so
+    /// their changes are always significant.
+    fn is_significant(self, running: &str, available: &str) -> bool {
+        let (Ok(running), Ok(available)) = (Version::parse(running), Version::parse(available))
+        else {
+            return true;
+        };
+
+        match self {
+            Granularity::Patch => true,
+            Granularity::Minor => {
+                (available.major, available.minor) > (running.major, running.minor)
+            }
+            Granularity::Major => available.major > running.major,
+        }
+    }
+}
+
+/// Streams the `versions` array of a crates.io API response into a callback.
+///
+/// Every other field of the response is skipped without being allocated, and
+/// each version is handed over as soon as it's decoded, so the full list never
+/// has to be held in memory.
+struct VersionStream<F>(F);
+
+impl<'de, F: FnMut(VersionInfo)> DeserializeSeed<'de> for VersionStream<F> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, F: FnMut(VersionInfo)> Visitor<'de> for VersionStream<F> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("a crates.io crate response")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(mut self, mut map: A) -> Result<(), A::Error> {
+        let mut found = false;
+
+        while let Some(key) = map.next_key::<String>()? {
+            if key == "versions" && !found {
+                map.next_value_seed(VersionList(&mut self.0))?;
+                found = true;
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+
+        if !found {
+            return Err(serde::de::Error::missing_field("versions"));
+        }
+        Ok(())
+    }
+}
+
+/// Streams the elements of the `versions` array into a callback.
+struct VersionList<'a, F>(&'a mut F);
+
+impl<'de, F: FnMut(VersionInfo)> DeserializeSeed<'de> for VersionList<'_, F> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, F: FnMut(VersionInfo)> Visitor<'de> for VersionList<'_, F> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("a list of versions")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        while let Some(version) = seq.next_element::<VersionInfo>()? {
+            (self.0)(version);
+        }
+        Ok(())
+    }
+}
+
+/// Reader that keeps a copy of the first bytes read through it, so a response
+/// that fails to parse can still be reported with a snippet.
+struct SnippetReader<R> {
+    /// The underlying reader
+    inner: R,
+    /// The first bytes read, up to [`PARSE_SNIPPET_LEN`]
+    snippet: Vec<u8>,
+}
+
+impl<R: Read> Read for SnippetReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        let wanted = (PARSE_SNIPPET_LEN - self.snippet.len()).min(n);
+        self.snippet.extend_from_slice(&buf[..wanted]);
+        Ok(n)
+    }
 }
 
 /// Information about a specific crate version from crates.io.
-#[derive(Deserialize)]
-struct VersionInfo {
+#[derive(Clone, Deserialize)]
+pub(crate) struct VersionInfo {
     /// Version number string (e.g., "1.0.0")
-    num: String,
+    pub(crate) num: String,
     /// RFC3339 timestamp of when this version was published
-    created_at: String,
+    #[serde(default)]
+    pub(crate) created_at: Option<String>,
+    /// Whether this version has been yanked
+    pub(crate) yanked: bool,
+    /// Why this version was yanked, if the registry says
+    #[serde(default)]
+    pub(crate) yank_message: Option<String>,
+    /// Minimum supported Rust version declared by this version, if any
+    #[serde(default)]
+    pub(crate) rust_version: Option<String>,
+}
+
+/// A published version of a crate.
+///
+/// This is the public counterpart of the version records fetched from crates.io.
+/// Build a list of these to run the update comparison over your own data with
+/// [`UpdateChecker::check_against`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublicVersionInfo {
+    /// Name of the crate
+    pub name: String,
+    /// Version number string (e.g., "1.0.0")
+    pub num: String,
+    /// RFC3339 timestamp of when this version was published (if known)
+    pub created_at: Option<String>,
     /// Whether this version has been yanked
-    yanked: bool,
+    pub yanked: bool,
+    /// Why this version was yanked, if known
+    #[serde(default)]
+    pub yank_message: Option<String>,
+    /// Minimum supported Rust version declared by this version, if any
+    #[serde(default)]
+    pub rust_version: Option<String>,
+}
+
+impl From<&PublicVersionInfo> for VersionInfo {
+    fn from(info: &PublicVersionInfo) -> Self {
+        VersionInfo {
+            num: info.num.clone(),
+            created_at: info.created_at.clone(),
+            yanked: info.yanked,
+            yank_message: info.yank_message.clone(),
+            rust_version: info.rust_version.clone(),
+        }
+    }
+}
+
+/// A snapshot of an [`UpdateChecker`]'s effective settings.
+///
+/// Returned by [`UpdateChecker::config`]. Changing it has no effect on the
+/// checker; use the builder methods for that.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CheckerConfig {
+    /// Whether the cache is bypassed on every check
+    pub bypass_cache: bool,
+    /// How long check results stay cached
+    pub cache_ttl: Duration,
+    /// Whether a fresh cache hit extends the entry's lifetime
+    pub sliding_cache: bool,
+    /// Maximum number of checks run in parallel by batch methods
+    pub concurrency: usize,
+    /// The User-Agent sent with registry requests
+    pub user_agent: String,
+    /// The registry API queried, or `None` if a custom source is used
+    pub registry_url: Option<String>,
+    /// Whether yanked releases count towards `versions_behind`
+    pub count_yanked_in_behind: bool,
+    /// Timeout for registry requests
+    pub timeout: Duration,
+    /// Per-crate overrides of `timeout`
+    pub crate_timeouts: HashMap<String, Duration>,
+    /// Smallest version change reported as an update
+    pub granularity: Granularity,
+    /// Maximum number of redirects followed by registry requests
+    pub max_redirects: usize,
+    /// Maximum number of connections opened to the registry at once
+    pub max_connections_per_host: usize,
+    /// How long after the first run checks are suppressed, if at all
+    pub initial_grace: Option<Duration>,
+    /// Minimum time between cache saves, if saves are deferred
+    pub min_flush_interval: Option<Duration>,
+    /// Crates whose versions use post-release semantics
+    pub post_release_crates: HashSet<String>,
+}
+
+/// A lifecycle event of the persistent cache.
+///
+/// See [`UpdateChecker::on_cache_event`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CacheEvent {
+    /// The cache was saved to a store that held no readable cache
+    Created,
+    /// A cache written by an older release was converted to the current format
+    Migrated,
+    /// The stored cache couldn't be read and was discarded
+    Corrupt,
+    /// The stored cache was loaded into memory, after any migration
+    Loaded {
+        /// Number of cached check results
+        entries: usize,
+    },
+}
+
+/// State of deferred cache saves, shared between clones of a checker.
+#[derive(Default)]
+struct FlushState {
+    /// Whether the cache store holds a readable cache
+    persisted: AtomicBool,
+    /// Whether the cache changed since it was last saved
+    dirty: AtomicBool,
+    /// When the cache was last saved, if it has been
+    last_flush: Mutex<Option<Instant>>,
+}
+
+/// Caps how many registry requests are in flight at once, shared between
+/// clones of a checker.
+pub(crate) struct ConnectionLimit {
+    /// Maximum number of requests in flight
+    max: usize,
+    /// Number of requests currently in flight
+    in_flight: Mutex<usize>,
+    /// Signalled whenever a request finishes
+    released: Condvar,
+}
+
+impl ConnectionLimit {
+    /// Creates a limit of `max` requests in flight (at least 1).
+    pub(crate) fn new(max: usize) -> Self {
+        ConnectionLimit {
+            max: max.max(1),
+            in_flight: Mutex::new(0),
+            released: Condvar::new(),
+        }
+    }
+
+    /// Blocks until a request may start, and returns a permit that lets the
+    /// next one start when dropped.
+    pub(crate) fn acquire(&self) -> ConnectionPermit<'_> {
+        // The count is only ever changed by a single statement, so it stays
+        // consistent even if a holder of the lock panicked
+        let mut in_flight = self
+            .in_flight
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        while *in_flight >= self.max {
+            in_flight = self
+                .released
+                .wait(in_flight)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+        *in_flight += 1;
+        ConnectionPermit(self)
+    }
+}
+
+/// Permission to have one registry request in flight. See [`ConnectionLimit`].
+pub(crate) struct ConnectionPermit<'a>(&'a ConnectionLimit);
+
+impl Drop for ConnectionPermit<'_> {
+    fn drop(&mut self) {
+        let mut in_flight = self
+            .0
+            .in_flight
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        *in_flight -= 1;
+        self.0.released.notify_one();
+    }
+}
+
+/// Main update checker with caching support.
+///
+/// # Examples
+///
+/// ```no_run
+/// use updates::UpdateChecker;
+///
+/// // Create a new checker with caching enabled
+/// let checker = UpdateChecker::new(false);
+///
+/// // Check if serde needs an update
+/// if let Some(result) = checker.check("serde", "1.0.150") {
+///     println!("{}", result);
+///     println!("Please update to: {}", result.available_version);
+/// } else {
+///     println!("You're up to date!");
+/// }
+/// ```
+///
+/// ```no_run
+/// use updates::UpdateChecker;
+///
+/// // Create a checker that always queries crates.io (bypasses cache)
+/// let checker = UpdateChecker::new(true);
+///
+/// match checker.check("tokio", "1.0.0") {
+///     Some(update) => println!("Update available: {}", update.available_version),
+///     None => println!("Already on latest version"),
+/// }
+/// ```
+///
+/// Only update checks use the cache. Lookups such as
+/// [`versions_behind`](Self::versions_behind),
+/// [`yanked_between`](Self::yanked_between),
+/// [`latest_version`](Self::latest_version),
+/// [`latest_version_info`](Self::latest_version_info) and
+/// [`latest_both`](Self::latest_both) always read the configured
+/// [`VersionSource`] (crates.io by default) and skip the cache.
+///
+/// Cloning is cheap, and clones share the same cache and cache store, so a
+/// result cached through one clone is visible to all of them:
+///
+/// ```no_run
+/// use updates::UpdateChecker;
+///
+/// let checker = UpdateChecker::new(false);
+/// let background = checker.clone();
+///
+/// let handle = std::thread::spawn(move || background.check("serde", "1.0.150"));
+/// handle.join().unwrap();
+///
+/// // Answered from the cache populated by the background check
+/// checker.check("serde", "1.0.150");
+/// ```
+#[derive(Clone)]
+pub struct UpdateChecker {
+    /// Whether to bypass the cache on every check
+    bypass_cache: bool,
+    /// Whether a fresh cache hit extends the entry's lifetime
+    sliding_cache: bool,
+    /// Maximum number of checks run in parallel by batch methods
+    concurrency: usize,
+    /// Name and version of the application embedding this crate
+    app_identity: Option<(String, String)>,
+    /// Callback invoked after every registry request
+    request_observer: Option<RequestObserver>,
+    /// Callback invoked on persistent cache lifecycle events
+    cache_observer: Option<CacheObserver>,
+    /// Events from the most recent cache load, if the store held any data
+    load_events: Vec<CacheEvent>,
+    /// Where versions are read from instead of the crates.io API, if set
+    source: Option<Arc<dyn VersionSource>>,
+    /// Whether yanked releases count towards `versions_behind`
+    count_yanked_in_behind: bool,
+    /// Timeout for registry requests
+    timeout: Duration,
+    /// Per-crate overrides of `timeout`
+    crate_timeouts: HashMap<String, Duration>,
+    /// Maximum number of redirects followed by registry requests
+    max_redirects: usize,
+    /// Maximum number of connections opened to the registry at once
+    max_connections_per_host: usize,
+    /// Limit on registry requests in flight, shared between clones
+    connections: Arc<ConnectionLimit>,
+    /// HTTP client for registry requests, built on first use and shared
+    /// between clones so they reuse its connection pool
+    client: Arc<OnceLock<reqwest::blocking::Client>>,
+    /// How long after the first run checks are suppressed, if at all
+    initial_grace: Option<Duration>,
+    /// Minimum time between cache saves, if saves are deferred
+    min_flush_interval: Option<Duration>,
+    /// State of deferred cache saves, shared between clones
+    flush_state: Arc<FlushState>,
+    /// Ranks of the prerelease tags used to order versions
+    prerelease_tags: PrereleaseTags,
+    /// Crates whose versions use post-release semantics
+    post_release_crates: HashSet<String>,
+    /// Smallest version change reported as an update
+    granularity: Granularity,
+    /// Where `notify` reports available updates
+    notifier: Arc<dyn Notifier>,
+    /// In-memory cache of check results, shared between clones
+    pub(crate) cache: Arc<Mutex<HashMap<(String, String), CacheEntry>>>,
+    /// Where the cache is persisted between runs
+    cache_store: Arc<dyn CacheStore>,
+}
+
+impl UpdateChecker {
+    /// Creates a new UpdateChecker instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `bypass_cache` - If `true`, always queries crates.io instead of using cached results.
+    ///                    If `false`, uses cached results for up to 1 hour.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use updates::UpdateChecker;
+    ///
+    /// // With caching (recommended for most use cases)
+    /// let checker = UpdateChecker::new(false);
+    ///
+    /// // Without caching (always fetch fresh data)
+    /// let checker_no_cache = UpdateChecker::new(true);
+    /// ```
+    pub fn new(bypass_cache: bool) -> Self {
+        UpdateChecker::with_store(bypass_cache, FileCacheStore::default())
+    }
+
+    /// Creates a new UpdateChecker that persists its cache with a custom
+    /// [`CacheStore`].
+    ///
+    /// Unlike [`new`](Self::new) followed by
+    /// [`with_cache_store`](Self::with_cache_store), the default cache file in
+    /// the system's temp directory is never touched. Use this on platforms
+    /// without a usable temp directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `bypass_cache` - If `true`, always queries crates.io instead of using cached results.
+    /// * `store` - Where to load and save the cache
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use updates::{MemoryCacheStore, UpdateChecker};
+    ///
+    /// let checker = UpdateChecker::with_store(false, MemoryCacheStore::default());
+    /// ```
+    pub fn with_store(bypass_cache: bool, store: impl CacheStore + 'static) -> Self {
+        let mut checker = UpdateChecker {
+            bypass_cache,
+            sliding_cache: false,
+            concurrency: DEFAULT_CONCURRENCY,
+            app_identity: None,
+            request_observer: None,
+            cache_observer: None,
+            load_events: Vec::new(),
+            source: None,
+            count_yanked_in_behind: false,
+            timeout: DEFAULT_TIMEOUT,
+            crate_timeouts: HashMap::new(),
+            max_redirects: DEFAULT_MAX_REDIRECTS,
+            max_connections_per_host: DEFAULT_MAX_CONNECTIONS_PER_HOST,
+            connections: Arc::new(ConnectionLimit::new(DEFAULT_MAX_CONNECTIONS_PER_HOST)),
+            client: Arc::new(OnceLock::new()),
+            initial_grace: None,
+            min_flush_interval: None,
+            flush_state: Arc::new(FlushState::default()),
+            prerelease_tags: PrereleaseTags::default(),
+            post_release_crates: HashSet::new(),
+            granularity: Granularity::Patch,
+            notifier: Arc::new(StderrNotifier),
+            cache: Arc::new(Mutex::new(HashMap::new())),
+            cache_store: Arc::new(store),
+        };
+
+        checker.load_from_permacache();
+        checker
+    }
+
+    /// Enables or disables sliding cache expiry.
+    ///
+    /// By default, cache entries expire a fixed hour after they were written,
+    /// regardless of how often they are read. With sliding expiry enabled, every
+    /// fresh cache hit resets the entry's timestamp to the current time, so
+    /// actively-used entries stay cached for as long as they keep being checked.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - If `true`, a fresh cache hit refreshes the entry's timestamp.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use updates::UpdateChecker;
+    ///
+    /// let checker = UpdateChecker::new(false).sliding_cache(true);
+    /// ```
+    pub fn sliding_cache(mut self, enabled: bool) -> Self {
+        self.sliding_cache = enabled;
+        self
+    }
+
+    /// Sets how many checks batch methods may run in parallel.
+    ///
+    /// Defaults to 4. Values below 1 are treated as 1.
+    ///
+    /// # Arguments
+    ///
+    /// * `concurrency` - Maximum number of in-flight checks
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use updates::UpdateChecker;
+    ///
+    /// let checker = UpdateChecker::new(false).concurrency(8);
+    /// ```
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Identifies the application embedding this crate to the registry.
+    ///
+    /// By default, requests are sent with this crate's own User-Agent, which makes
+    /// every tool using it indistinguishable in crates.io's logs. Setting an app
+    /// identity sends `{name}/{version} (via updates)` instead. Pass your own
+    /// crate's `CARGO_PKG_NAME` and `CARGO_PKG_VERSION`, since `env!` in this
+    /// crate would only ever see its own.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of your application
+    /// * `version` - The version of your application
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use updates::UpdateChecker;
+    ///
+    /// let checker = UpdateChecker::new(false)
+    ///     .with_app_identity(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+    /// ```
+    pub fn with_app_identity(mut self, name: &str, version: &str) -> Self {
+        self.app_identity = Some((name.to_string(), version.to_string()));
+        self
+    }
+
+    /// Registers a callback that observes every request made to the registry.
+    ///
+    /// The callback is invoked after each HTTP request with the URL, the time it
+    /// took, and the response status, or `Err(())` if the request failed before a
+    /// response was received. Cache hits don't make requests, so they don't
+    /// invoke it. This is useful for feeding request counts and latencies into
+    /// metrics.
+    ///
+    /// # Arguments
+    ///
+    /// * `observer` - The callback to invoke after each request
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use updates::UpdateChecker;
+    ///
+    /// let checker = UpdateChecker::new(false).on_request(|url, elapsed, outcome| {
+    ///     eprintln!("GET {} took {:?}: {:?}", url, elapsed, outcome);
+    /// });
+    /// ```
+    pub fn on_request(
+        mut self,
+        observer: impl Fn(&str, Duration, Result<StatusCode, ()>) + Send + Sync + 'static,
+    ) -> Self {
+        self.request_observer = Some(Arc::new(observer));
+        self
+    }
+
+    /// Registers a callback invoked on persistent cache lifecycle events.
+    ///
+    /// Useful to diagnose cache issues in the field: the callback learns when
+    /// the cache is loaded, when it's first written, and when stored data is
+    /// discarded as unreadable. Since the cache is loaded when the checker is
+    /// constructed, the outcome of the most recent load is reported as soon as
+    /// the callback is registered.
+    ///
+    /// # Arguments
+    ///
+    /// * `observer` - The callback to invoke on each event
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use updates::{CacheEvent, UpdateChecker};
+    ///
+    /// let checker = UpdateChecker::new(false).on_cache_event(|event| {
+    ///     if *event == CacheEvent::Corrupt {
+    ///         eprintln!("update cache was unreadable and has been reset");
+    ///     }
+    /// });
+    /// ```
+    pub fn on_cache_event(
+        mut self,
+        observer: impl Fn(&CacheEvent) + Send + Sync + 'static,
+    ) -> Self {
+        self.load_events.iter().for_each(&observer);
+
+        self.cache_observer = Some(Arc::new(observer));
+        self
+    }
+
+    /// Reports a cache lifecycle event to the registered callback, if any.
+    fn emit_cache_event(&self, event: CacheEvent) {
+        if let Some(observer) = &self.cache_observer {
+            observer(&event);
+        }
+    }
+
+    /// Reads crate versions from a custom source instead of the crates.io API.
+    ///
+    /// Every check then runs against `source`, using the same selection and
+    /// comparison logic. Combine this with an [`IndexSource`](crate::IndexSource)
+    /// for fully offline, reproducible audits. Consider also bypassing the cache
+    /// so results from different sources don't mix.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The source to read versions from
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use updates::{IndexSource, UpdateChecker};
+    ///
+    /// let checker = UpdateChecker::new(true).with_source(IndexSource::new("./crates.io-index"));
+    /// ```
+    pub fn with_source(mut self, source: impl VersionSource + 'static) -> Self {
+        self.source = Some(Arc::new(source));
+        self
+    }
+
+    /// Reports updates found by [`notify`](Self::notify) through a custom notifier.
+    ///
+    /// By default, notices are printed to stderr. Use a
+    /// [`LogNotifier`](crate::LogNotifier) to write compact lines to a log, a
+    /// [`NoopNotifier`](crate::NoopNotifier) to stay silent, or your own
+    /// [`Notifier`] implementation.
+    ///
+    /// # Arguments
+    ///
+    /// * `notifier` - Where available updates are reported
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use updates::{LogNotifier, UpdateChecker};
+    ///
+    /// let checker = UpdateChecker::new(false).with_notifier(LogNotifier::new(std::io::stdout()));
+    /// checker.notify("my-tool", env!("CARGO_PKG_VERSION"));
+    /// ```
+    pub fn with_notifier(mut self, notifier: impl Notifier + 'static) -> Self {
+        self.notifier = Arc::new(notifier);
+        self
+    }
+
+    /// Sets the smallest version change that is reported as an update.
+    ///
+    /// With [`Granularity::Minor`], an update is only returned if its major or
+    /// minor component increased, so `1.2.0 → 1.2.5` is ignored but
+    /// `1.2.0 → 1.3.0` is reported. Unlike
+    /// [`check_patch_only`](Self::check_patch_only), which restricts which
+    /// version is offered, this suppresses updates that are too small to be
+    /// worth a notice. A yanked running version is always reported.
+    ///
+    /// Defaults to [`Granularity::Patch`], which reports every newer version.
+    ///
+    /// # Arguments
+    ///
+    /// * `granularity` - The smallest version change to report
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use updates::{Granularity, UpdateChecker};
+    ///
+    /// let checker = UpdateChecker::new(false).notify_granularity(Granularity::Minor);
+    /// ```
+    pub fn notify_granularity(mut self, granularity: Granularity) -> Self {
+        self.granularity = granularity;
+        self
+    }
+
+    /// Sets whether yanked releases count towards [`versions_behind`](Self::versions_behind).
+    ///
+    /// By default, yanked releases are excluded, matching how the latest version
+    /// is chosen. Enable this to count every release published since yours,
+    /// yanked or not.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - If `true`, yanked releases are counted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use updates::UpdateChecker;
+    ///
+    /// let checker = UpdateChecker::new(false).count_yanked_in_behind(true);
+    /// ```
+    pub fn count_yanked_in_behind(mut self, enabled: bool) -> Self {
+        self.count_yanked_in_behind = enabled;
+        self
+    }
+
+    /// Sets the timeout for registry requests.
+    ///
+    /// Defaults to 3 seconds. Individual crates can be given a different
+    /// timeout with [`with_crate_timeout`](Self::with_crate_timeout).
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - How long to wait for the registry to respond
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use updates::UpdateChecker;
+    ///
+    /// let checker = UpdateChecker::new(false).with_timeout(Duration::from_secs(2));
+    /// ```
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Overrides the request timeout for a single crate.
+    ///
+    /// Useful in batch checks where a few crates with huge version histories
+    /// need longer than the rest, without slowing down the whole batch.
+    ///
+    /// # Arguments
+    ///
+    /// * `crate_name` - The crate to override the timeout for
+    /// * `timeout` - How long to wait for the registry to respond for this crate
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use updates::UpdateChecker;
+    ///
+    /// let checker = UpdateChecker::new(false)
+    ///     .with_timeout(Duration::from_secs(2))
+    ///     .with_crate_timeout("serde", Duration::from_secs(5));
+    /// ```
+    pub fn with_crate_timeout(mut self, crate_name: &str, timeout: Duration) -> Self {
+        self.crate_timeouts.insert(crate_name.to_string(), timeout);
+        self
+    }
+
+    /// Sets how many redirects registry requests follow before giving up.
+    ///
+    /// Defaults to 5. Lower it to stop a misbehaving proxy from bouncing
+    /// requests around, or set it to 0 to treat any redirect as a failure.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_redirects` - The maximum number of redirects to follow
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use updates::UpdateChecker;
+    ///
+    /// let checker = UpdateChecker::new(false).with_max_redirects(2);
+    /// ```
+    pub fn with_max_redirects(mut self, max_redirects: usize) -> Self {
+        self.max_redirects = max_redirects;
+        self.client = Arc::new(OnceLock::new());
+        self
+    }
+
+    /// Sets how many connections are opened to the registry at once.
+    ///
+    /// Every request goes to the same host, so this caps how many registry
+    /// requests are in flight at once across the checker and all its clones,
+    /// including direct [`check`](Self::check) calls from several threads.
+    /// Further requests wait until one finishes. It also caps how many checks
+    /// batch methods run in parallel, whatever the
+    /// [`concurrency`](Self::concurrency), and how many idle connections are
+    /// kept open for reuse. Defaults to 4.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_connections` - The maximum number of connections (at least 1)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use updates::UpdateChecker;
+    ///
+    /// let checker = UpdateChecker::new(false).with_max_connections_per_host(2);
+    /// ```
+    pub fn with_max_connections_per_host(mut self, max_connections: usize) -> Self {
+        self.max_connections_per_host = max_connections.max(1);
+        self.connections = Arc::new(ConnectionLimit::new(max_connections));
+        self.client = Arc::new(OnceLock::new());
+        self
+    }
+
+    /// Suppresses checks until a crate has been in use for a while.
+    ///
+    /// The first check of a crate records when it ran and returns `None`, and
+    /// later checks keep returning `None` until `grace` has passed since then.
+    /// This avoids nagging users about updates right after they installed your
+    /// tool. The first run is recorded in the cache, so it persists between
+    /// runs for as long as the cache does.
+    ///
+    /// [`refresh`](Self::refresh) ignores the grace period.
+    ///
+    /// # Arguments
+    ///
+    /// * `grace` - How long after the first run to suppress checks
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use updates::UpdateChecker;
+    ///
+    /// // Stay quiet for the first three days
+    /// let checker =
+    ///     UpdateChecker::new(false).with_initial_grace(Duration::from_secs(3 * 24 * 60 * 60));
+    /// ```
+    pub fn with_initial_grace(mut self, grace: Duration) -> Self {
+        self.initial_grace = Some(grace);
+        self
+    }
+
+    /// Coalesces cache saves so the store is written at most once per `interval`.
+    ///
+    /// By default, the cache is saved after every change. For long-running
+    /// processes that check continuously, that's a lot of writes. With a
+    /// minimum flush interval, a change is saved right away only if the last
+    /// save was at least `interval` ago; otherwise it's held in memory.
+    ///
+    /// No timer or background thread is involved: held-back changes are saved
+    /// by the next change that comes due, an explicit [`flush`](Self::flush),
+    /// or when the last clone sharing the cache is dropped. Call `flush`
+    /// yourself if changes must reach the store sooner.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - The minimum time between saves
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use updates::UpdateChecker;
+    ///
+    /// let checker = UpdateChecker::new(false).with_min_flush_interval(Duration::from_secs(60));
+    /// ```
+    pub fn with_min_flush_interval(mut self, interval: Duration) -> Self {
+        self.min_flush_interval = Some(interval);
+        self
+    }
+
+    /// Saves any cache changes held back by the
+    /// [minimum flush interval](Self::with_min_flush_interval) to the cache store.
+    ///
+    /// Does nothing if there are none, which is always the case without a
+    /// minimum flush interval.
+    pub fn flush(&self) {
+        if self.flush_state.dirty.load(Ordering::SeqCst) {
+            self.write_permacache();
+        }
+    }
+
+    /// Returns a snapshot of the checker's effective settings.
+    ///
+    /// Useful to debug why a check did (or didn't) hit the network, or to test
+    /// the effect of builder calls.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use updates::UpdateChecker;
+    ///
+    /// let checker = UpdateChecker::new(true).with_timeout(Duration::from_secs(5));
+    /// let config = checker.config();
+    ///
+    /// assert!(config.bypass_cache);
+    /// assert_eq!(config.timeout, Duration::from_secs(5));
+    /// ```
+    pub fn config(&self) -> CheckerConfig {
+        CheckerConfig {
+            bypass_cache: self.bypass_cache,
+            cache_ttl: Duration::from_secs(CACHE_EXPIRE_TIME),
+            sliding_cache: self.sliding_cache,
+            concurrency: self.concurrency,
+            user_agent: self.user_agent(),
+            registry_url: match self.source {
+                Some(_) => None,
+                None => Some(CRATES_IO_API_URL.to_string()),
+            },
+            count_yanked_in_behind: self.count_yanked_in_behind,
+            timeout: self.timeout,
+            crate_timeouts: self.crate_timeouts.clone(),
+            granularity: self.granularity,
+            max_redirects: self.max_redirects,
+            max_connections_per_host: self.max_connections_per_host,
+            initial_grace: self.initial_grace,
+            min_flush_interval: self.min_flush_interval,
+            post_release_crates: self.post_release_crates.clone(),
+        }
+    }
+
+    /// Returns `true` if checks of `crate_name` are still suppressed by the
+    /// initial grace period, recording the first run if this is it.
+    fn in_initial_grace(&self, crate_name: &str) -> bool {
+        let Some(grace) = self.initial_grace else {
+            return false;
+        };
+
+        let now = unix_now();
+        let key = (crate_name.to_string(), FIRST_RUN_MARKER.to_string());
+        let first_run = match self.cache.lock() {
+            Ok(locked_cache) => locked_cache.get(&key).map(|entry| entry.timestamp),
+            Err(_) => return false,
+        };
+
+        match first_run {
+            Some(first_run) => now.saturating_sub(first_run) < grace.as_secs(),
+            None => {
+                self.store(
+                    key,
+                    CacheEntry {
+                        timestamp: now,
+                        result: None,
+                        error: None,
+                    },
+                );
+                true
+            }
+        }
+    }
+
+    /// Registers a prerelease tag, or changes the rank of a built-in one.
+    ///
+    /// Versions are ordered by comparing their components, and prerelease tags
+    /// are compared by rank. Every ranked tag orders before the final release.
+    /// The built-in ranks are:
+    ///
+    /// | Tag                          | Rank |
+    /// |------------------------------|------|
+    /// | `dev`, `nightly`, `snapshot` | 10   |
+    /// | `alpha`, `a`                 | 20   |
+    /// | `beta`, `b`                  | 30   |
+    /// | `milestone`                  | 35   |
+    /// | `rc`, `pre`, `preview`, `c`  | 40   |
+    ///
+    /// Tags that aren't recognised are compared alphabetically, with `dev`,
+    /// `alpha`, `beta` and `rc` standing in as `@`, `a`, `b` and `c`: so
+    /// `beta < build < rc`. An unrecognised tag orders before the final release
+    /// only if it sorts before `final` (e.g. `canary`), and after it otherwise
+    /// (e.g. `unstable`), so register such tags to order them correctly. A tag
+    /// ranked between two built-in ranks orders after the lower one and any
+    /// unrecognised tags starting with its letter.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag` - The tag to register (case-insensitive, letters only)
+    /// * `rank` - Where the tag orders relative to the other tags
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use updates::UpdateChecker;
+    ///
+    /// // 1.0.0-beta1 < 1.0.0-unstable1 < 1.0.0-rc1 < 1.0.0
+    /// let checker = UpdateChecker::new(false).with_prerelease_tag("unstable", 35);
+    /// ```
+    pub fn with_prerelease_tag(mut self, tag: &str, rank: u8) -> Self {
+        self.prerelease_tags.insert(tag, rank);
+        self
+    }
+
+    /// Orders a crate's versions with post-release semantics.
+    ///
+    /// Following semver, a suffix such as `1.0.0-post1` marks a prerelease,
+    /// which is older than `1.0.0` and only offered to prerelease users. Crates
+    /// mirrored from ecosystems with PEP 440-style post-releases mean the
+    /// opposite: `1.0.0-post1` (or `1.0.0.post1`) is a fix published after
+    /// `1.0.0`, and before `1.0.1`.
+    ///
+    /// For the given crate, versions tagged `post`, `rev` or `r` are treated as
+    /// releases that order after the version they follow, with the number after
+    /// the tag ordering post-releases of the same version. Running versions in
+    /// the dotted form, which isn't valid semver, are accepted too. Other
+    /// crates keep the standard behaviour.
+    ///
+    /// # Arguments
+    ///
+    /// * `crate_name` - The crate whose versions use post-releases
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use updates::UpdateChecker;
+    ///
+    /// // 1.0.0 < 1.0.0-post1 < 1.0.0-post2 < 1.0.1
+    /// let checker = UpdateChecker::new(false).with_post_releases("my-mirrored-crate");
+    /// ```
+    pub fn with_post_releases(mut self, crate_name: &str) -> Self {
+        self.post_release_crates.insert(crate_name.to_string());
+        self
+    }
+
+    /// Returns the prerelease tags used to order a crate's versions.
+    fn tags_for(&self, crate_name: &str) -> Cow<'_, PrereleaseTags> {
+        if self.post_release_crates.contains(crate_name) {
+            let mut tags = self.prerelease_tags.clone();
+            tags.post_releases = true;
+            Cow::Owned(tags)
+        } else {
+            Cow::Borrowed(&self.prerelease_tags)
+        }
+    }
+
+    /// Returns the request timeout to use for a crate.
+    pub(crate) fn timeout_for(&self, crate_name: &str) -> Duration {
+        self.crate_timeouts
+            .get(crate_name)
+            .copied()
+            .unwrap_or(self.timeout)
+    }
+
+    /// Persists the cache with a custom [`CacheStore`] instead of a file in the
+    /// system's temp directory.
+    ///
+    /// The in-memory cache is replaced with whatever `store` has saved. The
+    /// checker stops sharing its cache with clones made before this call.
+    /// Since the checker was already loaded from the default store, use
+    /// [`with_store`](Self::with_store) instead on platforms without a usable
+    /// temp directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `store` - Where to load and save the cache
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use updates::{FileCacheStore, UpdateChecker};
+    ///
+    /// let checker = UpdateChecker::new(false)
+    ///     .with_cache_store(FileCacheStore::new("/var/lib/my-tool/updates.bin"));
+    /// ```
+    pub fn with_cache_store(mut self, store: impl CacheStore + 'static) -> Self {
+        self.cache_store = Arc::new(store);
+        self.cache = Arc::new(Mutex::new(HashMap::new()));
+        self.flush_state = Arc::new(FlushState::default());
+
+        self.load_from_permacache();
+        self
+    }
+
+    /// Loads cached data from the cache store into memory.
+    fn load_from_permacache(&mut self) {
+        self.load_events = match self.cache_store.load().map(|data| decode_cache(&data)) {
+            None => Vec::new(),
+            Some(Ok((cache, migrated))) => {
+                let entries = cache
+                    .keys()
+                    .filter(|(_, version)| version != FIRST_RUN_MARKER)
+                    .count();
+                if let Ok(mut locked_cache) = self.cache.lock() {
+                    *locked_cache = cache;
+                }
+
+                let loaded = CacheEvent::Loaded { entries };
+                if migrated {
+                    vec![CacheEvent::Migrated, loaded]
+                } else {
+                    vec![loaded]
+                }
+            }
+            Some(Err(_)) => vec![CacheEvent::Corrupt],
+        };
+
+        let persisted = self
+            .load_events
+            .iter()
+            .any(|event| matches!(event, CacheEvent::Loaded { .. }));
+        self.flush_state
+            .persisted
+            .store(persisted, Ordering::SeqCst);
+        for event in self.load_events.clone() {
+            self.emit_cache_event(event);
+        }
+    }
+
+    /// Saves the current in-memory cache to the cache store, or defers it if
+    /// the last save was less than the minimum flush interval ago.
+    fn save_to_permacache(&self) {
+        if let Some(interval) = self.min_flush_interval {
+            self.flush_state.dirty.store(true, Ordering::SeqCst);
+            let due = match self.flush_state.last_flush.lock() {
+                Ok(last_flush) => last_flush.is_none_or(|at| at.elapsed() >= interval),
+                Err(_) => true,
+            };
+            if !due {
+                return;
+            }
+        }
+
+        self.write_permacache();
+    }
+
+    /// Writes the current in-memory cache to the cache store.
+    fn write_permacache(&self) {
+        self.flush_state.dirty.store(false, Ordering::SeqCst);
+        if let Ok(mut last_flush) = self.flush_state.last_flush.lock() {
+            *last_flush = Some(Instant::now());
+        }
+
+        // Release the cache before calling out, so stores and callbacks can
+        // use the checker
+        let data = match self.cache.lock() {
+            Ok(locked_cache) => encode_cache(&locked_cache),
+            Err(_) => return,
+        };
+        let Ok(data) = data else {
+            return;
+        };
+
+        self.cache_store.save(&data);
+        if !self.flush_state.persisted.swap(true, Ordering::SeqCst) {
+            self.emit_cache_event(CacheEvent::Created);
+        }
+    }
+
+    /// Merges cache state from an in-memory byte slice.
+    ///
+    /// The data must be in the format produced by
+    /// [`dump_cache_bytes`](Self::dump_cache_bytes), or a cache written by an
+    /// older release. Entries from `data` replace
+    /// any existing entries with the same key. This is useful for deterministic
+    /// tests or for shipping a baked-in cache with a binary.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The serialised cache bytes
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the data was loaded and merged
+    /// * `Err(UpdateError::Cache)` - If the data could not be deserialised
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use updates::UpdateChecker;
+    ///
+    /// let source = UpdateChecker::new(false);
+    /// let bytes = source.dump_cache_bytes();
+    ///
+    /// let mut checker = UpdateChecker::new(true);
+    /// checker.load_cache_bytes(&bytes).unwrap();
+    /// ```
+    pub fn load_cache_bytes(&mut self, data: &[u8]) -> Result<(), UpdateError> {
+        let (cache, _) = decode_cache(data)?;
+
+        let mut locked_cache = self
+            .cache
+            .lock()
+            .map_err(|e| UpdateError::Cache(e.to_string()))?;
+        locked_cache.extend(cache);
+        Ok(())
+    }
+
+    /// Serialises the current in-memory cache to bytes.
+    ///
+    /// The output can be fed back into [`load_cache_bytes`](Self::load_cache_bytes)
+    /// or embedded into a binary with `include_bytes!`.
+    pub fn dump_cache_bytes(&self) -> Vec<u8> {
+        self.cache
+            .lock()
+            .ok()
+            .and_then(|locked_cache| encode_cache(&locked_cache).ok())
+            .unwrap_or_default()
+    }
+
+    /// Checks if a newer version of a crate is available.
+    ///
+    /// # Arguments
+    ///
+    /// * `crate_name` - The name of the crate to check (e.g., "serde")
+    /// * `crate_version` - The current version you're using (e.g., "1.0.150")
+    ///
+    /// # Returns
+    ///
+    /// * `Some(UpdateResult)` - If a newer version is available
+    /// * `None` - If you're already on the latest version, `crate_version` isn't
+    ///   valid semver, or the query fails
+    ///
+    /// Failures are treated as "no update available", which suits
+    /// non-intrusive startup checks. Use [`try_check`](Self::try_check) to
+    /// handle them yourself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use updates::UpdateChecker;
+    ///
+    /// let checker = UpdateChecker::new(false);
+    ///
+    /// // Check a stable release
+    /// if let Some(update) = checker.check("regex", "1.5.0") {
+    ///     println!("Regex update available: {}", update.available_version);
+    /// }
+    ///
+    /// // Check a prerelease (will also consider other prereleases)
+    /// if let Some(update) = checker.check("tokio", "1.0.0-alpha.1") {
+    ///     println!("Tokio prerelease update: {}", update.available_version);
+    /// }
+    /// ```
+    pub fn check(&self, crate_name: &str, crate_version: &str) -> Option<UpdateResult> {
+        if self.in_initial_grace(crate_name) {
+            return None;
+        }
+
+        self.check_inner(crate_name, crate_version, !self.bypass_cache)
+            .ok()
+            .flatten()
+    }
+
+    /// Checks if a newer version of a crate is available, also returning the
+    /// result cached before this check.
+    ///
+    /// Useful to only alert when the available version changes: compare the
+    /// two and stay quiet if they match. The previous result is read whether
+    /// or not it is still fresh. If it is, the check is answered from the
+    /// cache and both results are the same.
+    ///
+    /// # Arguments
+    ///
+    /// * `crate_name` - The name of the crate to check (e.g., "serde")
+    /// * `crate_version` - The current version you're using (e.g., "1.0.150")
+    ///
+    /// # Returns
+    ///
+    /// A `(current, previous)` tuple, where each is the update available at
+    /// that point, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use updates::UpdateChecker;
+    ///
+    /// let checker = UpdateChecker::new(false);
+    ///
+    /// if let (Some(current), previous) = checker.check_with_previous("my-tool", "1.0.0") {
+    ///     let changed = previous
+    ///         .is_none_or(|previous| previous.available_version != current.available_version);
+    ///     if changed {
+    ///         eprintln!("{}", current);
+    ///     }
+    /// }
+    /// ```
+    pub fn check_with_previous(
+        &self,
+        crate_name: &str,
+        crate_version: &str,
+    ) -> (Option<UpdateResult>, Option<UpdateResult>) {
+        let key = (crate_name.to_string(), crate_version.to_string());
+        let previous = self
+            .cache
+            .lock()
+            .ok()
+            .and_then(|locked_cache| locked_cache.get(&key).and_then(|e| e.result.clone()));
+
+        (
+            self.check(crate_name, crate_version),
+            self.filter_granularity(previous),
+        )
+    }
+
+    /// Checks if a newer version of a crate is available, taking owned arguments.
+    ///
+    /// This is identical to [`check`](Self::check), but accepts owned strings so
+    /// they can be moved into a spawned thread or closure without borrowing from
+    /// the caller.
+    ///
+    /// # Arguments
+    ///
+    /// * `crate_name` - The name of the crate to check (e.g., "serde")
+    /// * `crate_version` - The current version you're using (e.g., "1.0.150")
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    /// use updates::UpdateChecker;
+    ///
+    /// let checker = Arc::new(UpdateChecker::new(false));
+    /// let name = String::from("serde");
+    /// let version = String::from("1.0.150");
+    ///
+    /// let handle = {
+    ///     let checker = Arc::clone(&checker);
+    ///     std::thread::spawn(move || checker.check_owned(name, version))
+    /// };
+    ///
+    /// if let Some(update) = handle.join().unwrap() {
+    ///     println!("{}", update);
+    /// }
+    /// ```
+    pub fn check_owned(
+        &self,
+        crate_name: impl Into<String>,
+        crate_version: impl Into<String>,
+    ) -> Option<UpdateResult> {
+        self.check(&crate_name.into(), &crate_version.into())
+    }
+
+    /// Checks if a newer version of a crate is available, giving up if the
+    /// whole check takes longer than `budget`.
+    ///
+    /// Unlike the request [`timeout`](Self::with_timeout), the budget covers
+    /// everything, including DNS resolution and the TLS handshake. The check
+    /// runs on a background thread; if it doesn't finish in time, this returns
+    /// `None` and the thread is left to finish on its own, so its result is
+    /// still cached for the next check.
+    ///
+    /// # Arguments
+    ///
+    /// * `crate_name` - The name of the crate to check (e.g., "serde")
+    /// * `crate_version` - The current version you're using (e.g., "1.0.150")
+    /// * `budget` - The longest the caller is willing to wait
+    ///
+    /// # Returns
+    ///
+    /// * `Some(UpdateResult)` - If a newer version is available
+    /// * `None` - If already on the latest version, the check failed, or it
+    ///   didn't finish within `budget`
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use updates::UpdateChecker;
+    ///
+    /// let checker = UpdateChecker::new(false);
+    /// let budget = Duration::from_millis(300);
+    ///
+    /// if let Some(update) = checker.check_within_budget("my-tool", "1.0.0", budget) {
+    ///     eprintln!("{}", update);
+    /// }
+    /// ```
+    pub fn check_within_budget(
+        &self,
+        crate_name: &str,
+        crate_version: &str,
+        budget: Duration,
+    ) -> Option<UpdateResult> {
+        let checker = self.clone();
+        let crate_name = crate_name.to_string();
+        let crate_version = crate_version.to_string();
+        let (tx, rx) = mpsc::channel();
+
+        std::thread::spawn(move || {
+            let _ = tx.send(checker.check_owned(crate_name, crate_version));
+        });
+
+        rx.recv_timeout(budget).ok().flatten()
+    }
+
+    /// Checks if a newer version of a crate is available, and reports it
+    /// through the checker's notifier.
+    ///
+    /// This behaves like [`check`](Self::check), but additionally hands any
+    /// available update to the notifier set with
+    /// [`with_notifier`](Self::with_notifier), which prints to stderr by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `crate_name` - The name of the crate to check
+    /// * `crate_version` - The current version string
+    ///
+    /// # Returns
+    ///
+    /// * `Some(UpdateResult)` - If a newer version is available (and was reported)
+    /// * `None` - If already on the latest version, or if the check failed
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use updates::UpdateChecker;
+    ///
+    /// UpdateChecker::new(false).notify("my-tool", env!("CARGO_PKG_VERSION"));
+    /// ```
+    pub fn notify(&self, crate_name: &str, crate_version: &str) -> Option<UpdateResult> {
+        let result = self.check(crate_name, crate_version)?;
+        self.notifier.notify(&result);
+        Some(result)
+    }
+
+    /// Checks if a newer version of a crate is available, reporting failures.
+    ///
+    /// This behaves like [`check`](Self::check), but returns a `Result`:
+    /// network and registry failures are returned as errors instead of being
+    /// treated as "no update". Failures are cached like any other result, so
+    /// a cached failure is returned again until it expires.
+    ///
+    /// # Arguments
+    ///
+    /// * `crate_name` - The name of the crate to check (e.g., "serde")
+    /// * `crate_version` - The current version you're using (e.g., "1.0.150")
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(UpdateResult))` - If a newer version is available
+    /// * `Ok(None)` - If you're already on the latest version
+    /// * `Err(UpdateError::InvalidVersion)` - If `crate_version` isn't valid semver
+    /// * `Err(UpdateError)` - If the check failed
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use updates::UpdateChecker;
+    ///
+    /// let checker = UpdateChecker::new(true);
+    ///
+    /// match checker.try_check("serde", "1.0.150") {
+    ///     Ok(Some(update)) => println!("{}", update),
+    ///     Ok(None) => println!("You're up to date!"),
+    ///     Err(e) => eprintln!("Could not check for updates: {}", e),
+    /// }
+    /// ```
+    pub fn try_check(
+        &self,
+        crate_name: &str,
+        crate_version: &str,
+    ) -> Result<Option<UpdateResult>, UpdateError> {
+        if self.in_initial_grace(crate_name) {
+            return Ok(None);
+        }
+
+        self.check_inner(crate_name, crate_version, !self.bypass_cache)
+    }
+
+    /// Shared implementation of [`check`](Self::check), [`try_check`](Self::try_check)
+    /// and [`refresh`](Self::refresh). If `read_cache` is `false`, a fresh cache
+    /// entry is ignored (the result is still written). Query failures, including
+    /// cached ones, are returned as errors. An invalid `crate_version` is
+    /// rejected before the cache or the registry is consulted.
+    fn check_inner(
+        &self,
+        crate_name: &str,
+        crate_version: &str,
+        read_cache: bool,
+    ) -> Result<Option<UpdateResult>, UpdateError> {
+        let tags = self.tags_for(crate_name);
+        validate_version(crate_version, &tags)?;
+
+        let now = unix_now();
+
+        let key = (crate_name.to_string(), crate_version.to_string());
+
+        // Check cache
+        if read_cache {
+            let mut hit = None;
+            if let Ok(mut locked_cache) = self.cache.lock() {
+                if let Some(entry) = locked_cache.get_mut(&key) {
+                    if entry.is_fresh(now) {
+                        if self.sliding_cache {
+                            entry.timestamp = now;
+                        }
+                        hit = Some(entry.clone());
+                    }
+                }
+            }
+
+            if let Some(entry) = hit {
+                if self.sliding_cache {
+                    self.save_to_permacache();
+                }
+                if let Some(error) = entry.error {
+                    return Err(error);
+                }
+                return Ok(self.filter_granularity(entry.result));
+            }
+        }
+
+        // Query crates.io
+        let mut candidates = Candidates::new(crate_version, &tags);
+        let result = self
+            .stream_versions(crate_name, |version| candidates.offer(version))
+            .and_then(|()| {
+                compare_versions(
+                    crate_name,
+                    crate_version,
+                    &candidates.into_versions(),
+                    &tags,
+                    |_| true,
+                )
+            });
+
+        let result = match result {
+            Ok(result) => result,
+            Err(error) => {
+                // Failures are cached too, so an unreachable registry doesn't
+                // cost a timeout on every check
+                self.store(
+                    key,
+                    CacheEntry {
+                        timestamp: now,
+                        result: None,
+                        error: Some(error.clone()),
+                    },
+                );
+                return Err(error);
+            }
+        };
+
+        self.store(
+            key,
+            CacheEntry {
+                timestamp: now,
+                result: result.clone(),
+                error: None,
+            },
+        );
+        Ok(self.filter_granularity(result))
+    }
+
+    /// Drops `result` if its version change is smaller than the configured
+    /// [`Granularity`].
+    ///
+    /// This is applied after the cache, so cached results don't depend on it.
+    fn filter_granularity(&self, result: Option<UpdateResult>) -> Option<UpdateResult> {
+        result.filter(|r| {
+            r.running_yanked
+                || self
+                    .granularity
+                    .is_significant(&r.running_version, &r.available_version)
+        })
+    }
+
+    /// Inserts an entry into the in-memory cache and persists it to disk.
+    fn store(&self, key: (String, String), entry: CacheEntry) {
+        if let Ok(mut locked_cache) = self.cache.lock() {
+            locked_cache.insert(key, entry);
+        }
+
+        self.save_to_permacache();
+    }
+
+    /// Returns a snapshot of every entry in the in-memory cache.
+    ///
+    /// Entries are cloned, so the snapshot doesn't change as the checker keeps
+    /// running. Stale entries are included; compare the cached-at time against
+    /// the current time if you only want fresh ones.
+    ///
+    /// # Returns
+    ///
+    /// A `(crate_name, crate_version, result, cached_at)` tuple per entry, in no
+    /// particular order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use updates::UpdateChecker;
+    ///
+    /// let checker = UpdateChecker::new(false);
+    ///
+    /// for (name, version, result, cached_at) in checker.cached_entries() {
+    ///     let status = if result.is_some() { "outdated" } else { "up to date" };
+    ///     println!("{} {}: {} (cached at {:?})", name, version, status, cached_at);
+    /// }
+    /// ```
+    pub fn cached_entries(&self) -> Vec<(String, String, Option<UpdateResult>, SystemTime)> {
+        let Ok(locked_cache) = self.cache.lock() else {
+            return Vec::new();
+        };
+
+        locked_cache
+            .iter()
+            .filter(|((_, version), _)| version != FIRST_RUN_MARKER)
+            .map(|((name, version), entry)| {
+                (
+                    name.clone(),
+                    version.clone(),
+                    entry.result.clone(),
+                    UNIX_EPOCH + Duration::from_secs(entry.timestamp),
+                )
+            })
+            .collect()
+    }
+
+    /// Returns every cached update, without querying the registry.
+    ///
+    /// This is a read of the in-memory cache only, so it never touches the
+    /// network. Updates smaller than the configured
+    /// [`notify_granularity`](Self::notify_granularity) are left out, as they
+    /// would be by [`check`](Self::check).
+    ///
+    /// # Arguments
+    ///
+    /// * `fresh_only` - If `true`, updates cached longer ago than the cache
+    ///   lifetime are left out.
+    ///
+    /// # Returns
+    ///
+    /// The cached updates, sorted by crate name and running version.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use updates::UpdateChecker;
+    ///
+    /// let checker = UpdateChecker::new(false);
+    ///
+    /// for update in checker.outdated_from_cache(true) {
+    ///     println!("{}", update.short());
+    /// }
+    /// ```
+    pub fn outdated_from_cache(&self, fresh_only: bool) -> Vec<UpdateResult> {
+        let now = unix_now();
+        let Ok(locked_cache) = self.cache.lock() else {
+            return Vec::new();
+        };
+
+        let mut outdated: Vec<UpdateResult> = locked_cache
+            .values()
+            .filter(|entry| !fresh_only || entry.is_fresh(now))
+            .filter_map(|entry| self.filter_granularity(entry.result.clone()))
+            .collect();
+        outdated.sort_by(|a, b| {
+            (&a.crate_name, &a.running_version).cmp(&(&b.crate_name, &b.running_version))
+        });
+        outdated
+    }
+
+    /// Returns how long the cached result for a crate version stays fresh.
+    ///
+    /// # Arguments
+    ///
+    /// * `crate_name` - The name of the crate
+    /// * `crate_version` - The version that was checked
+    ///
+    /// # Returns
+    ///
+    /// * `Some(Duration)` - The time left before the cached entry expires
+    /// * `None` - If there is no fresh cached entry
+    pub fn cache_ttl_remaining(&self, crate_name: &str, crate_version: &str) -> Option<Duration> {
+        let now = unix_now();
+        let key = (crate_name.to_string(), crate_version.to_string());
+        let locked_cache = self.cache.lock().ok()?;
+        let entry = locked_cache.get(&key)?;
+
+        entry
+            .is_fresh(now)
+            .then(|| Duration::from_secs(entry.remaining(now)))
+    }
+
+    /// Returns how long to wait before the next check of a crate version would
+    /// query the registry.
+    ///
+    /// While a fresh cache entry exists, checking again would just return the
+    /// cached result, so this is the time left until that entry expires. If
+    /// there is no fresh entry, or the cache is bypassed, this is zero: the next
+    /// check will fetch live data. During the
+    /// [initial grace period](Self::with_initial_grace), checks return `None`
+    /// without querying, so this is at least the time left until it ends.
+    /// Long-running applications can use this to schedule a timer instead of
+    /// polling.
+    ///
+    /// # Arguments
+    ///
+    /// * `crate_name` - The name of the crate
+    /// * `crate_version` - The version being checked
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use updates::UpdateChecker;
+    ///
+    /// let checker = UpdateChecker::new(false);
+    /// checker.check("serde", "1.0.150");
+    ///
+    /// let wait = checker.next_check_after("serde", "1.0.150");
+    /// println!("Next check in {:?}", wait);
+    /// ```
+    pub fn next_check_after(&self, crate_name: &str, crate_version: &str) -> Duration {
+        let cached = if self.bypass_cache {
+            Duration::ZERO
+        } else {
+            self.cache_ttl_remaining(crate_name, crate_version)
+                .unwrap_or(Duration::ZERO)
+        };
+
+        cached.max(self.initial_grace_remaining(crate_name))
+    }
+
+    /// Returns how long checks of a crate are still suppressed by the initial
+    /// grace period, without starting it.
+    ///
+    /// If the crate has never been checked, its first check starts the grace
+    /// period, so the full period remains.
+    fn initial_grace_remaining(&self, crate_name: &str) -> Duration {
+        let Some(grace) = self.initial_grace else {
+            return Duration::ZERO;
+        };
+
+        let key = (crate_name.to_string(), FIRST_RUN_MARKER.to_string());
+        let first_run = match self.cache.lock() {
+            Ok(locked_cache) => locked_cache.get(&key).map(|entry| entry.timestamp),
+            Err(_) => return Duration::ZERO,
+        };
+
+        match first_run {
+            Some(first_run) => {
+                let elapsed = Duration::from_secs(unix_now().saturating_sub(first_run));
+                grace.saturating_sub(elapsed)
+            }
+            None => grace,
+        }
+    }
+
+    /// Checks a single crate against live data and updates its cache entry.
+    ///
+    /// This ignores any cached result for this crate version, fetches fresh data,
+    /// and stores it, while the rest of the cache keeps serving cached results.
+    /// It's more surgical than a second checker with `bypass_cache` enabled, e.g.
+    /// for an interactive "check again" action.
+    ///
+    /// # Arguments
+    ///
+    /// * `crate_name` - The name of the crate to check (e.g., "serde")
+    /// * `crate_version` - The current version you're using (e.g., "1.0.150")
+    ///
+    /// # Returns
+    ///
+    /// * `Some(UpdateResult)` - If a newer version is available
+    /// * `None` - If you're already on the latest version or if the query fails
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use updates::UpdateChecker;
+    ///
+    /// let checker = UpdateChecker::new(false);
+    ///
+    /// if let Some(update) = checker.refresh("serde", "1.0.150") {
+    ///     println!("{}", update);
+    /// }
+    /// ```
+    pub fn refresh(&self, crate_name: &str, crate_version: &str) -> Option<UpdateResult> {
+        self.check_inner(crate_name, crate_version, false)
+            .ok()
+            .flatten()
+    }
+
+    /// Checks for a newer version using live data, without touching the cache.
+    ///
+    /// Unlike [`check`](Self::check) with `bypass_cache` enabled, which skips
+    /// reading the cache but still writes the fresh result to it, this neither
+    /// reads nor writes the cache. Use it for one-off authoritative checks,
+    /// diagnostics, or tests that must not affect the shared cache.
+    ///
+    /// # Arguments
+    ///
+    /// * `crate_name` - The name of the crate to check (e.g., "serde")
+    /// * `crate_version` - The current version you're using (e.g., "1.0.150")
+    ///
+    /// # Returns
+    ///
+    /// * `Some(UpdateResult)` - If a newer version is available
+    /// * `None` - If you're already on the latest version or if the query fails
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use updates::UpdateChecker;
+    ///
+    /// let checker = UpdateChecker::new(false);
+    ///
+    /// if let Some(update) = checker.peek_fresh("serde", "1.0.150") {
+    ///     println!("{}", update);
+    /// }
+    /// ```
+    pub fn peek_fresh(&self, crate_name: &str, crate_version: &str) -> Option<UpdateResult> {
+        self.check_filtered(crate_name, crate_version, |_| true)
+    }
+
+    /// Checks several running versions of the same crate with a single fetch.
+    ///
+    /// The version list is fetched once and every entry in `versions` is
+    /// compared against it, which is much cheaper than calling
+    /// [`check`](Self::check) for each. Results are not cached.
+    ///
+    /// # Arguments
+    ///
+    /// * `crate_name` - The name of the crate to check (e.g., "serde")
+    /// * `versions` - The running versions to compare
+    ///
+    /// # Returns
+    ///
+    /// A `(version, result)` pair for every input, in the same order. If the
+    /// query fails, every result is `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use updates::UpdateChecker;
+    ///
+    /// let checker = UpdateChecker::new(false);
+    ///
+    /// for (version, result) in checker.check_versions("serde", &["1.0.100", "1.0.150"]) {
+    ///     match result {
+    ///         Some(update) => println!("{}: outdated, {} available", version, update.available_version),
+    ///         None => println!("{}: up to date", version),
+    ///     }
+    /// }
+    /// ```
+    pub fn check_versions(
+        &self,
+        crate_name: &str,
+        versions: &[&str],
+    ) -> Vec<(String, Option<UpdateResult>)> {
+        let published = self.fetch_versions(crate_name).ok();
+        let tags = self.tags_for(crate_name);
+
+        versions
+            .iter()
+            .map(|version| {
+                let result = published.as_ref().and_then(|published| {
+                    compare_versions(crate_name, version, published, &tags, |_| true)
+                        .ok()
+                        .flatten()
+                });
+                (version.to_string(), result)
+            })
+            .collect()
+    }
+
+    /// Returns `true` as soon as any of the given crates has an update available.
+    ///
+    /// Crates are checked one at a time with [`check`](Self::check), so the
+    /// cache is respected and populated for every crate that was checked. The
+    /// remaining crates are skipped once an update is found, which makes this
+    /// cheaper than [`check_many`](Self::check_many) when only a yes/no answer
+    /// is needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `crates` - `(crate_name, crate_version)` pairs to check
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use updates::UpdateChecker;
+    ///
+    /// let checker = UpdateChecker::new(false);
+    ///
+    /// if checker.any_outdated(&[("serde", "1.0.150"), ("regex", "1.5.0")]) {
+    ///     eprintln!("Some dependencies are outdated, run `cargo update`");
+    /// }
+    /// ```
+    pub fn any_outdated(&self, crates: &[(&str, &str)]) -> bool {
+        crates
+            .iter()
+            .any(|(crate_name, crate_version)| self.check(crate_name, crate_version).is_some())
+    }
+
+    /// Checks several crates in parallel, returning results in input order.
+    ///
+    /// Up to [`concurrency`](Self::concurrency) checks run at once. Each check
+    /// behaves exactly like [`check`](Self::check), including caching.
+    ///
+    /// # Arguments
+    ///
+    /// * `crates` - `(crate_name, crate_version)` pairs to check
+    ///
+    /// # Returns
+    ///
+    /// A `(crate_name, result)` pair for every input, in the same order.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use updates::UpdateChecker;
+    ///
+    /// let checker = UpdateChecker::new(false);
+    ///
+    /// for (name, result) in checker.check_many(&[("serde", "1.0.150"), ("regex", "1.5.0")]) {
+    ///     if let Some(update) = result {
+    ///         println!("{}: {}", name, update);
+    ///     }
+    /// }
+    /// ```
+    pub fn check_many(&self, crates: &[(&str, &str)]) -> Vec<(String, Option<UpdateResult>)> {
+        let mut results: Vec<Option<UpdateResult>> = vec![None; crates.len()];
+        self.run_batch(crates, |index, result| results[index] = result);
+
+        crates
+            .iter()
+            .map(|(name, _)| name.to_string())
+            .zip(results)
+            .collect()
+    }
+
+    /// Checks several crates in parallel, handing each result to `sink` as soon
+    /// as it completes.
+    ///
+    /// Unlike [`check_many`](Self::check_many), results are delivered
+    /// incrementally and in no particular order, which suits printing progress
+    /// during large audits. The sink runs on the calling thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `crates` - `(crate_name, crate_version)` pairs to check
+    /// * `sink` - Called with `(crate_name, result)` for every input
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use updates::UpdateChecker;
+    ///
+    /// let checker = UpdateChecker::new(false).concurrency(8);
+    ///
+    /// checker.check_many_streaming(&[("serde", "1.0.150"), ("regex", "1.5.0")], |_, result| {
+    ///     if let Some(update) = result {
+    ///         println!("{}", update);
+    ///     }
+    /// });
+    /// ```
+    pub fn check_many_streaming(
+        &self,
+        crates: &[(&str, &str)],
+        mut sink: impl FnMut(String, Option<UpdateResult>),
+    ) {
+        self.run_batch(crates, |index, result| {
+            sink(crates[index].0.to_string(), result)
+        });
+    }
+
+    /// Runs [`check`](Self::check) over `crates` on a pool of worker threads,
+    /// calling `on_result` with each input's index as results arrive.
+    fn run_batch(
+        &self,
+        crates: &[(&str, &str)],
+        mut on_result: impl FnMut(usize, Option<UpdateResult>),
+    ) {
+        let next = AtomicUsize::new(0);
+        let (tx, rx) = mpsc::channel();
+
+        std::thread::scope(|scope| {
+            let workers = self
+                .concurrency
+                .min(self.max_connections_per_host)
+                .min(crates.len());
+            for _ in 0..workers {
+                let tx = tx.clone();
+                let next = &next;
+                scope.spawn(move || {
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some((name, version)) = crates.get(index) else {
+                            break;
+                        };
+
+                        if tx.send((index, self.check(name, version))).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+            drop(tx);
+
+            for (index, result) in rx {
+                on_result(index, result);
+            }
+        });
+    }
+
+    /// Checks for the newest version of a crate that supports a given Rust toolchain.
+    ///
+    /// Only versions whose declared `rust-version` (MSRV) is less than or equal to
+    /// `rustc_version` are considered. Versions that don't declare an MSRV are
+    /// assumed to be compatible. Results are not cached, since they depend on the
+    /// toolchain being asked about.
+    ///
+    /// # Arguments
+    ///
+    /// * `crate_name` - The name of the crate to check (e.g., "serde")
+    /// * `crate_version` - The current version you're using (e.g., "1.0.150")
+    /// * `rustc_version` - The Rust version you're building with (e.g., "1.65")
+    ///
+    /// # Returns
+    ///
+    /// * `Some(UpdateResult)` - If a newer MSRV-compatible version is available
+    /// * `None` - If nothing newer supports `rustc_version` or if the query fails
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use updates::UpdateChecker;
+    ///
+    /// let checker = UpdateChecker::new(false);
+    ///
+    /// if let Some(update) = checker.check_for_rust_version("clap", "4.0.0", "1.65") {
+    ///     println!("Newest clap for Rust 1.65: {}", update.available_version);
+    /// }
+    /// ```
+    pub fn check_for_rust_version(
+        &self,
+        crate_name: &str,
+        crate_version: &str,
+        rustc_version: &str,
+    ) -> Option<UpdateResult> {
+        self.check_filtered(crate_name, crate_version, |v| {
+            supports_rust_version(v, rustc_version)
+        })
+    }
+
+    /// Checks for the newest patch release within the current `major.minor` series.
+    ///
+    /// For conservative upgrades: from `1.4.2`, only `1.4.x` releases are
+    /// considered, and the newest non-yanked one is returned if it is newer.
+    /// Results are not cached.
+    ///
+    /// # Arguments
+    ///
+    /// * `crate_name` - The name of the crate to check (e.g., "serde")
+    /// * `crate_version` - The current version you're using (e.g., "1.0.150")
+    ///
+    /// # Returns
+    ///
+    /// * `Some(UpdateResult)` - If a newer patch release is available
+    /// * `None` - If you're on the newest patch, `crate_version` isn't valid
+    ///   semver, or the query fails
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use updates::UpdateChecker;
+    ///
+    /// let checker = UpdateChecker::new(false);
+    ///
+    /// if let Some(update) = checker.check_patch_only("regex", "1.5.0") {
+    ///     println!("Patch release available: {}", update.available_version);
+    /// }
+    /// ```
+    pub fn check_patch_only(&self, crate_name: &str, crate_version: &str) -> Option<UpdateResult> {
+        let current = Version::parse(crate_version).ok()?;
+        self.check_filtered(crate_name, crate_version, |v| {
+            same_minor_series(v, &current)
+        })
+    }
+
+    /// Checks for the newest version released on or after `since`.
+    ///
+    /// Useful for staged rollouts: e.g. pass the time of your last release
+    /// train to only be told about versions published since then. Versions
+    /// without a known release date never qualify. Results are not cached.
+    ///
+    /// # Arguments
+    ///
+    /// * `crate_name` - The name of the crate to check (e.g., "serde")
+    /// * `crate_version` - The current version you're using (e.g., "1.0.150")
+    /// * `since` - The earliest release date to consider
+    ///
+    /// # Returns
+    ///
+    /// * `Some(UpdateResult)` - If a newer version was released since `since`
+    /// * `None` - If no newer version was released since then, or the query fails
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use chrono::{Duration, Utc};
+    /// use updates::UpdateChecker;
+    ///
+    /// let checker = UpdateChecker::new(false);
+    /// let last_deploy = Utc::now() - Duration::days(14);
+    ///
+    /// if let Some(update) = checker.check_since("serde", "1.0.150", last_deploy) {
+    ///     println!("Released since our last deploy: {}", update.available_version);
+    /// }
+    /// ```
+    pub fn check_since(
+        &self,
+        crate_name: &str,
+        crate_version: &str,
+        since: DateTime<Utc>,
+    ) -> Option<UpdateResult> {
+        self.check_filtered(crate_name, crate_version, |v| {
+            v.created_at
+                .as_deref()
+                .and_then(parse_release_date)
+                .is_some_and(|created_at| created_at >= since)
+        })
+    }
+
+    /// Counts how many releases of a crate are newer than the running version.
+    ///
+    /// Prereleases are only counted if `crate_version` is itself a prerelease.
+    /// Yanked releases are excluded unless
+    /// [`count_yanked_in_behind`](Self::count_yanked_in_behind) is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `crate_name` - The name of the crate to check (e.g., "serde")
+    /// * `crate_version` - The current version you're using (e.g., "1.0.150")
+    ///
+    /// # Returns
+    ///
+    /// * `Some(usize)` - The number of newer releases (zero if up to date)
+    /// * `None` - If `crate_version` is invalid or the query fails
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use updates::UpdateChecker;
+    ///
+    /// let checker = UpdateChecker::new(false);
+    ///
+    /// if let Some(behind) = checker.versions_behind("serde", "1.0.150") {
+    ///     println!("You are {} releases behind", behind);
+    /// }
+    /// ```
+    pub fn versions_behind(&self, crate_name: &str, crate_version: &str) -> Option<usize> {
+        let tags = self.tags_for(crate_name);
+        validate_version(crate_version, &tags).ok()?;
+        let versions = self.fetch_versions(crate_name).ok()?;
+        count_versions_behind(&versions, crate_version, self.count_yanked_in_behind, &tags).ok()
+    }
+
+    /// Returns `true` if the registry can be reached.
+    ///
+    /// This sends a single `HEAD` request to the registry API, using the
+    /// configured timeout, redirect limit and User-Agent (and any proxy set in
+    /// the environment). Any HTTP response counts as reachable, even an error
+    /// status, since it shows the network path works. Use it to skip a large
+    /// batch of checks up front when offline, instead of letting each one
+    /// time out.
+    ///
+    /// With a custom [`VersionSource`] set, no request is made and this always
+    /// returns `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use updates::UpdateChecker;
+    ///
+    /// let checker = UpdateChecker::new(false);
+    ///
+    /// if checker.registry_reachable() {
+    ///     let results = checker.check_many(&[("serde", "1.0.150"), ("tokio", "1.0.0")]);
+    /// }
+    /// ```
+    pub fn registry_reachable(&self) -> bool {
+        if self.source.is_some() {
+            return true;
+        }
+
+        let Ok(client) = self.http_client() else {
+            return false;
+        };
+        let _permit = self.connections.acquire();
+        client
+            .head(CRATES_IO_API_URL)
+            .header("User-Agent", self.user_agent())
+            .timeout(self.timeout)
+            .send()
+            .is_ok()
+    }
+
+    /// Returns the yanked versions of a crate released after `current`, up to
+    /// and including `latest`.
+    ///
+    /// Yanked intermediate releases often point to broken or vulnerable
+    /// versions, so security tooling can warn users not to step onto them on
+    /// the way to `latest`.
+    ///
+    /// # Arguments
+    ///
+    /// * `crate_name` - The name of the crate to look up (e.g., "serde")
+    /// * `current` - The version being upgraded from (excluded)
+    /// * `latest` - The version being upgraded to (included)
+    ///
+    /// # Returns
+    ///
+    /// The yanked versions in range, oldest first. Empty if there are none,
+    /// either bound is invalid, or the query fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use updates::UpdateChecker;
+    ///
+    /// let checker = UpdateChecker::new(false);
+    ///
+    /// for version in checker.yanked_between("my-tool", "1.0.0", "1.4.0") {
+    ///     eprintln!("warning: my-tool {} was yanked", version);
+    /// }
+    /// ```
+    pub fn yanked_between(&self, crate_name: &str, current: &str, latest: &str) -> Vec<String> {
+        let tags = self.tags_for(crate_name);
+        if validate_version(current, &tags).is_err() || validate_version(latest, &tags).is_err() {
+            return Vec::new();
+        }
+        let Ok(versions) = self.fetch_versions(crate_name) else {
+            return Vec::new();
+        };
+
+        yanked_between(&versions, current, latest, &tags).unwrap_or_default()
+    }
+
+    /// Returns the newest stable, non-yanked version of a crate.
+    ///
+    /// # Arguments
+    ///
+    /// * `crate_name` - The name of the crate to look up (e.g., "serde")
+    ///
+    /// # Returns
+    ///
+    /// * `Some(String)` - The newest stable version
+    /// * `None` - If the query fails or the crate has no stable release
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use updates::UpdateChecker;
+    ///
+    /// if let Some(version) = UpdateChecker::default().latest_version("serde") {
+    ///     println!("Latest serde: {}", version);
+    /// }
+    /// ```
+    pub fn latest_version(&self, crate_name: &str) -> Option<String> {
+        self.crates_io(crate_name, false)
+            .ok()
+            .map(|version_info| version_info.num)
+    }
+
+    /// Returns the full details of the newest non-yanked version of a crate.
+    ///
+    /// # Arguments
+    ///
+    /// * `crate_name` - The name of the crate to look up (e.g., "serde")
+    /// * `include_prereleases` - Whether prereleases may be returned
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(PublicVersionInfo)` - The newest matching version
+    /// * `Err(UpdateError)` - If the query fails, or the crate has no matching
+    ///   version (see [`UpdateError::AllYanked`] and
+    ///   [`UpdateError::NoSuitableVersion`])
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use updates::{UpdateChecker, UpdateError};
+    ///
+    /// fn newest(name: &str) -> Result<(), UpdateError> {
+    ///     let info = UpdateChecker::default().latest_version_info(name, false)?;
+    ///     println!("{} {} (published {:?})", info.name, info.num, info.created_at);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn latest_version_info(
+        &self,
+        crate_name: &str,
+        include_prereleases: bool,
+    ) -> Result<PublicVersionInfo, UpdateError> {
+        let version_info = self.crates_io(crate_name, include_prereleases)?;
+
+        Ok(PublicVersionInfo {
+            name: crate_name.to_string(),
+            num: version_info.num,
+            created_at: version_info.created_at,
+            yanked: version_info.yanked,
+            yank_message: version_info.yank_message,
+            rust_version: version_info.rust_version,
+        })
+    }
+
+    /// Returns the newest stable version of a crate, and the newest prerelease
+    /// if it's newer still.
+    ///
+    /// Both come from a single fetch, e.g. to show "stable: 1.4.0 (beta:
+    /// 1.5.0-rc.1 available)". Yanked versions are never returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `crate_name` - The name of the crate to look up (e.g., "serde")
+    ///
+    /// # Returns
+    ///
+    /// * `Some((stable, prerelease))` - The newest stable version, and the
+    ///   newest prerelease if it's newer than `stable`
+    /// * `None` - If the query fails or the crate has no stable release
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use updates::UpdateChecker;
+    ///
+    /// if let Some((stable, prerelease)) = UpdateChecker::default().latest_both("tokio") {
+    ///     match prerelease {
+    ///         Some(prerelease) => println!("stable: {} ({} available)", stable, prerelease),
+    ///         None => println!("stable: {}", stable),
+    ///     }
+    /// }
+    /// ```
+    pub fn latest_both(&self, crate_name: &str) -> Option<(String, Option<String>)> {
+        let versions = self.fetch_versions(crate_name).ok()?;
+        let tags = self.tags_for(crate_name);
+        let stable = select_version(&versions, false, &tags, |_| true).ok()?;
+        let newest = select_version(&versions, true, &tags, |_| true).ok();
+
+        let prerelease = newest
+            .filter(|newest| {
+                parse_version_with(&newest.num, &tags) > parse_version_with(&stable.num, &tags)
+            })
+            .map(|newest| newest.num.clone());
+        Some((stable.num.clone(), prerelease))
+    }
+
+    /// Fetches the version lists of several crates into a [`VersionSnapshot`].
+    ///
+    /// The snapshot can be saved and loaded by other processes, which then
+    /// check against it offline. Versions are read from the checker's source,
+    /// or from crates.io if none is set; the cache isn't used.
+    ///
+    /// # Arguments
+    ///
+    /// * `crate_names` - The crates to include in the snapshot
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(VersionSnapshot)` - Every published version of each crate
+    /// * `Err(UpdateError)` - If any crate's versions couldn't be fetched
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use updates::UpdateChecker;
+    ///
+    /// let snapshot = UpdateChecker::new(true).snapshot(&["serde", "regex"]).unwrap();
+    /// snapshot.save("versions.json").unwrap();
+    /// ```
+    pub fn snapshot(&self, crate_names: &[&str]) -> Result<VersionSnapshot, UpdateError> {
+        let fetched_at = Utc::now();
+        let crates = crate_names
+            .iter()
+            .map(|&name| {
+                let versions = self
+                    .fetch_versions(name)?
+                    .into_iter()
+                    .map(|version| PublicVersionInfo {
+                        name: name.to_string(),
+                        num: version.num,
+                        created_at: version.created_at,
+                        yanked: version.yanked,
+                        yank_message: version.yank_message,
+                        rust_version: version.rust_version,
+                    })
+                    .collect();
+                Ok((name.to_string(), versions))
+            })
+            .collect::<Result<_, UpdateError>>()?;
+
+        Ok(VersionSnapshot::new(fetched_at, crates))
+    }
+
+    /// Fetches live data and returns the newest version accepted by `filter`
+    /// if it is newer than `crate_version`. Does not touch the cache, and makes
+    /// no request if `crate_version` is invalid.
+    fn check_filtered(
+        &self,
+        crate_name: &str,
+        crate_version: &str,
+        filter: impl Fn(&VersionInfo) -> bool,
+    ) -> Option<UpdateResult> {
+        let tags = self.tags_for(crate_name);
+        validate_version(crate_version, &tags).ok()?;
+        let versions = self.fetch_versions(crate_name).ok()?;
+        compare_versions(crate_name, crate_version, &versions, &tags, filter).ok()?
+    }
+
+    /// Checks a version against a caller-supplied list of versions.
+    ///
+    /// This runs the same selection and comparison logic as [`check`](Self::check)
+    /// without any network access or caching, so you can reuse it over version
+    /// data you already have (from a mirror, a fixture, or your own index).
+    /// Yanked versions are ignored, and prereleases are only considered if
+    /// `crate_version` is itself a prerelease.
+    ///
+    /// # Arguments
+    ///
+    /// * `crate_version` - The current version you're using (e.g., "1.0.150")
+    /// * `versions` - The published versions to compare against. The crate name
+    ///   reported in the result is taken from the first entry.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(UpdateResult)` - If a newer version is in `versions`
+    /// * `None` - If you're already on the latest version or `versions` has no
+    ///   suitable candidate
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use updates::{PublicVersionInfo, UpdateChecker};
+    ///
+    /// let versions = vec![
+    ///     PublicVersionInfo {
+    ///         name: "my-tool".to_string(),
+    ///         num: "1.0.0".to_string(),
+    ///         created_at: None,
+    ///         yanked: false,
+    ///         yank_message: None,
+    ///         rust_version: None,
+    ///     },
+    ///     PublicVersionInfo {
+    ///         name: "my-tool".to_string(),
+    ///         num: "1.1.0".to_string(),
+    ///         created_at: Some("2024-05-01T12:00:00Z".to_string()),
+    ///         yanked: false,
+    ///         yank_message: None,
+    ///         rust_version: Some("1.70".to_string()),
+    ///     },
+    /// ];
+    ///
+    /// let checker = UpdateChecker::new(true);
+    /// let update = checker.check_against("1.0.0", &versions).unwrap();
+    /// assert_eq!(update.available_version, "1.1.0");
+    /// ```
+    pub fn check_against(
+        &self,
+        crate_version: &str,
+        versions: &[PublicVersionInfo],
+    ) -> Option<UpdateResult> {
+        let crate_name = &versions.first()?.name;
+        let versions: Vec<VersionInfo> = versions.iter().map(VersionInfo::from).collect();
+        compare_versions(
+            crate_name,
+            crate_version,
+            &versions,
+            &self.tags_for(crate_name),
+            |_| true,
+        )
+        .ok()?
+    }
+}
+
+impl Drop for UpdateChecker {
+    /// Saves any cache changes held back by the minimum flush interval, once
+    /// the last clone sharing the cache is dropped.
+    fn drop(&mut self) {
+        // `Arc::into_inner` succeeds for exactly one of several clones dropped
+        // at once, so the cache is saved once and never skipped
+        let flush_state = std::mem::take(&mut self.flush_state);
+        if let Some(flush_state) = Arc::into_inner(flush_state) {
+            self.flush_state = Arc::new(flush_state);
+            self.flush();
+        }
+    }
+}
+
+impl Default for UpdateChecker {
+    /// Creates an UpdateChecker with caching enabled, equivalent to
+    /// `UpdateChecker::new(false)`. The cache is stored in the system's temp
+    /// directory.
+    fn default() -> Self {
+        UpdateChecker::new(false)
+    }
+}
+
+impl UpdateChecker {
+    /// Queries crates.io for the latest version of a crate.
+    ///
+    /// # Arguments
+    ///
+    /// * `package` - The crate name to query
+    /// * `include_prereleases` - Whether to include prerelease versions (alpha, beta, rc, etc.)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(VersionInfo)` - The latest version information
+    /// * `Err` - If the query fails or no suitable version is found
+    fn crates_io(
+        &self,
+        package: &str,
+        include_prereleases: bool,
+    ) -> Result<VersionInfo, UpdateError> {
+        let versions = self.fetch_versions(package)?;
+        let version_info = select_version(
+            &versions,
+            include_prereleases,
+            &self.tags_for(package),
+            |_| true,
+        )?;
+
+        Ok(version_info.clone())
+    }
+
+    /// Fetches the full list of published versions of a crate from crates.io,
+    /// or from the configured [`VersionSource`] if one was set.
+    ///
+    /// # Arguments
+    ///
+    /// * `package` - The crate name to query
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<VersionInfo>)` - Every version crates.io knows about, including yanked ones
+    /// * `Err` - If the request fails or the response cannot be decoded
+    fn fetch_versions(&self, package: &str) -> Result<Vec<VersionInfo>, UpdateError> {
+        let mut versions = Vec::new();
+        self.stream_versions(package, |version| versions.push(version))?;
+        Ok(versions)
+    }
+
+    /// Fetches every published version of a crate like
+    /// [`fetch_versions`](Self::fetch_versions), but hands each version to `sink`
+    /// as it's decoded instead of collecting them.
+    ///
+    /// # Arguments
+    ///
+    /// * `package` - The crate name to query
+    /// * `sink` - Called with each version, in the order the registry lists them
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If every version was read
+    /// * `Err` - If the request fails or the response cannot be decoded
+    fn stream_versions(
+        &self,
+        package: &str,
+        mut sink: impl FnMut(VersionInfo),
+    ) -> Result<(), UpdateError> {
+        if let Some(source) = &self.source {
+            source
+                .versions(package)?
+                .iter()
+                .for_each(|version| sink(VersionInfo::from(version)));
+            return Ok(());
+        }
+
+        let url = format!("{}/{}", CRATES_IO_API_URL, package);
+        // Held until the body is read, since the connection is busy until then
+        let _permit = self.connections.acquire();
+        let started = Instant::now();
+        let response = self
+            .http_client()?
+            .get(&url)
+            .header("User-Agent", self.user_agent())
+            .timeout(self.timeout_for(package))
+            .send();
+
+        if let Some(observer) = &self.request_observer {
+            let outcome = response.as_ref().map(|r| r.status()).map_err(|_| ());
+            observer(&url, started.elapsed(), outcome);
+        }
+
+        let response = response.map_err(|e| UpdateError::Network(e.to_string()))?;
+
+        check_status(response.status())?;
+
+        parse_response_from(response, sink)
+    }
+
+    /// Returns the HTTP client for registry requests, building it with the
+    /// redirect and connection limits on first use.
+    fn http_client(&self) -> Result<reqwest::blocking::Client, UpdateError> {
+        if let Some(client) = self.client.get() {
+            return Ok(client.clone());
+        }
+
+        let client = reqwest::blocking::Client::builder()
+            .redirect(Policy::limited(self.max_redirects))
+            .pool_max_idle_per_host(self.max_connections_per_host)
+            .build()
+            .map_err(|e| UpdateError::Network(e.to_string()))?;
+        Ok(self.client.get_or_init(|| client).clone())
+    }
+
+    /// Builds the User-Agent sent with registry requests.
+    ///
+    /// Identifies the embedding application if one was set with
+    /// [`with_app_identity`](Self::with_app_identity), or this crate otherwise.
+    pub(crate) fn user_agent(&self) -> String {
+        match &self.app_identity {
+            Some((name, version)) => format!("{}/{} (via updates)", name, version),
+            None => format!(
+                "updates-rs/{} (+{})",
+                env!("CARGO_PKG_VERSION"),
+                env!("CARGO_PKG_REPOSITORY")
+            ),
+        }
+    }
+}
+
+/// Selects the newest non-yanked version that satisfies `filter`.
+///
+/// # Arguments
+///
+/// * `versions` - The versions to choose from
+/// * `include_prereleases` - Whether to include prerelease versions (alpha, beta, rc, etc.)
+/// * `tags` - Ranks of the prerelease tags used to order versions
+/// * `filter` - Additional predicate a version must satisfy to be considered
+///
+/// # Returns
+///
+/// * `Ok(&VersionInfo)` - The newest matching version
+/// * `Err(UpdateError::AllYanked)` - If there are no versions, or all are yanked
+/// * `Err(UpdateError::NoSuitableVersion)` - If no version satisfies the criteria
+pub(crate) fn select_version<'a>(
+    versions: &'a [VersionInfo],
+    include_prereleases: bool,
+    tags: &PrereleaseTags,
+    filter: impl Fn(&VersionInfo) -> bool,
+) -> Result<&'a VersionInfo, UpdateError> {
+    // Filter out yanked versions
+    let mut versions: Vec<&VersionInfo> = dedup_versions(versions)
+        .into_iter()
+        .filter(|v| !v.yanked)
+        .collect();
+
+    if versions.is_empty() {
+        return Err(UpdateError::AllYanked);
+    }
+
+    // Sort by version (newest first)
+    versions
+        .sort_by(|a, b| parse_version_with(&b.num, tags).cmp(&parse_version_with(&a.num, tags)));
+
+    // Find the best version based on prerelease preference
+    let version_info = versions
+        .into_iter()
+        .find(|v| (include_prereleases || tags.is_release(&v.num)) && filter(v))
+        .ok_or(UpdateError::NoSuitableVersion)?;
+
+    Ok(version_info)
+}
+
+/// Collapses entries that share a version number into one.
+///
+/// A well-behaved registry lists each version once, but a mirror may repeat
+/// one, e.g. once yanked and once not. The non-yanked copy is kept, then the
+/// one created last, so the outcome doesn't depend on the order of the list.
+///
+/// # Returns
+///
+/// One entry per version number, in the order each number first appears.
+pub(crate) fn dedup_versions(versions: &[VersionInfo]) -> Vec<&VersionInfo> {
+    let mut kept: Vec<&VersionInfo> = Vec::with_capacity(versions.len());
+    let mut positions: HashMap<&str, usize> = HashMap::new();
+
+    for version in versions {
+        match positions.get(version.num.as_str()) {
+            Some(&i) => {
+                if is_preferred_copy(version, kept[i]) {
+                    kept[i] = version;
+                }
+            }
+            None => {
+                positions.insert(&version.num, kept.len());
+                kept.push(version);
+            }
+        }
+    }
+
+    kept
+}
+
+/// Returns `true` if `candidate` should replace `current`, another entry for
+/// the same version number (see [`dedup_versions`]).
+fn is_preferred_copy(candidate: &VersionInfo, current: &VersionInfo) -> bool {
+    let rank = |v: &VersionInfo| {
+        (
+            !v.yanked,
+            v.created_at.as_deref().and_then(parse_release_date),
+        )
+    };
+
+    rank(candidate) > rank(current)
+}
+
+/// Maps a registry response status to an error if it isn't successful.
+///
+/// # Returns
+///
+/// * `Ok(())` - If the status is a success
+/// * `Err(UpdateError::NotFound)` - If the crate doesn't exist (404)
+/// * `Err(UpdateError::Http)` - For any other non-success status
+pub(crate) fn check_status(status: StatusCode) -> Result<(), UpdateError> {
+    if status == StatusCode::NOT_FOUND {
+        return Err(UpdateError::NotFound);
+    }
+
+    if !status.is_success() {
+        return Err(UpdateError::Http(status.as_u16()));
+    }
+
+    Ok(())
 }
 
-/// Main update checker with caching support.
+/// Maximum number of response bytes kept in [`UpdateError::Parse`] snippets.
+const PARSE_SNIPPET_LEN: usize = 200;
+
+/// Decodes a crates.io API response body into its list of versions.
 ///
-/// # Examples
+/// # Arguments
 ///
-/// ```no_run
-/// use updates::UpdateChecker;
+/// * `body` - The raw response body
 ///
-/// // Create a new checker with caching enabled
-/// let checker = UpdateChecker::new(false);
+/// # Returns
 ///
-/// // Check if serde needs an update
-/// if let Some(result) = checker.check("serde", "1.0.150") {
-///     println!("{}", result);
-///     println!("Please update to: {}", result.available_version);
-/// } else {
-///     println!("You're up to date!");
-/// }
-/// ```
+/// * `Ok(Vec<VersionInfo>)` - The versions listed in the response
+/// * `Err(UpdateError::Parse)` - If the body isn't the expected JSON, carrying
+///   the start of the body to help diagnose e.g. a mirror returning HTML
+#[cfg(test)]
+pub(crate) fn parse_response(body: &[u8]) -> Result<Vec<VersionInfo>, UpdateError> {
+    let mut versions = Vec::new();
+    parse_response_from(body, |version| versions.push(version))?;
+    Ok(versions)
+}
+
+/// Decodes a crates.io API response as it's read, handing each version to
+/// `sink` instead of buffering the body.
 ///
-/// ```no_run
-/// use updates::UpdateChecker;
+/// # Arguments
 ///
-/// // Create a checker that always queries crates.io (bypasses cache)
-/// let checker = UpdateChecker::new(true);
+/// * `reader` - The response body
+/// * `sink` - Called with each version, in the order the response lists them
 ///
-/// match checker.check("tokio", "1.0.0") {
-///     Some(update) => println!("Update available: {}", update.available_version),
-///     None => println!("Already on latest version"),
-/// }
-/// ```
-pub struct UpdateChecker {
-    /// Whether to bypass the cache on every check
-    bypass_cache: bool,
-    /// In-memory cache of check results
-    cache: std::sync::Mutex<HashMap<(String, String), CacheEntry>>,
-    /// Path to the persistent cache file
-    cache_file: Option<PathBuf>,
-}
-
-impl UpdateChecker {
-    /// Creates a new UpdateChecker instance.
-    ///
-    /// # Arguments
-    ///
-    /// * `bypass_cache` - If `true`, always queries crates.io instead of using cached results.
-    ///                    If `false`, uses cached results for up to 1 hour.
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// use updates::UpdateChecker;
-    ///
-    /// // With caching (recommended for most use cases)
-    /// let checker = UpdateChecker::new(false);
-    ///
-    /// // Without caching (always fetch fresh data)
-    /// let checker_no_cache = UpdateChecker::new(true);
-    /// ```
-    pub fn new(bypass_cache: bool) -> Self {
-        let cache_file = std::env::temp_dir().join("updates_cache.bin");
-
-        let mut checker = UpdateChecker {
-            bypass_cache,
-            cache: std::sync::Mutex::new(HashMap::new()),
-            cache_file: Some(cache_file),
-        };
-
-        checker.load_from_permacache();
-        checker
-    }
+/// # Returns
+///
+/// * `Ok(())` - If the whole response was decoded
+/// * `Err(UpdateError::Parse)` - If the body isn't the expected JSON, carrying
+///   the start of the body
+/// * `Err(UpdateError::Network)` - If reading the body failed
+pub(crate) fn parse_response_from(
+    reader: impl Read,
+    sink: impl FnMut(VersionInfo),
+) -> Result<(), UpdateError> {
+    let mut reader = BufReader::new(SnippetReader {
+        inner: reader,
+        snippet: Vec::new(),
+    });
+    let mut deserializer = serde_json::Deserializer::from_reader(&mut reader);
+    let result = VersionStream(sink)
+        .deserialize(&mut deserializer)
+        .and_then(|()| deserializer.end());
 
-    /// Loads cached data from disk into memory.
-    fn load_from_permacache(&mut self) {
-        if let Some(ref path) = self.cache_file {
-            if let Ok(data) = fs::read(path) {
-                if let Ok(cache) =
-                    postcard::from_bytes::<HashMap<(String, String), CacheEntry>>(&data)
-                {
-                    if let Ok(mut locked_cache) = self.cache.lock() {
-                        *locked_cache = cache;
-                    }
+    match result {
+        Ok(()) => Ok(()),
+        Err(e) if e.is_io() => Err(UpdateError::Network(e.to_string())),
+        Err(_) => {
+            // Pull in the rest of the snippet if parsing failed early
+            let mut snippet = reader.into_inner();
+            let mut rest = [0; PARSE_SNIPPET_LEN];
+            while snippet.snippet.len() < PARSE_SNIPPET_LEN {
+                match snippet.read(&mut rest) {
+                    Ok(0) | Err(_) => break,
+                    Ok(_) => {}
                 }
             }
+
+            Err(UpdateError::Parse {
+                snippet: String::from_utf8_lossy(&snippet.snippet).into_owned(),
+            })
         }
     }
+}
 
-    /// Saves the current in-memory cache to disk.
-    fn save_to_permacache(&self) {
-        if let Some(ref path) = self.cache_file {
-            if let Ok(locked_cache) = self.cache.lock() {
-                if let Ok(data) = postcard::to_allocvec(&*locked_cache) {
-                    let _ = fs::write(path, data);
-                }
-            }
+/// The few versions needed to check a single running version, accumulated
+/// while the full list is streamed.
+///
+/// Feeding every published version through [`offer`](Self::offer) and passing
+/// [`into_versions`](Self::into_versions) to [`compare_versions`] gives the same
+/// result as passing the full list, without holding it in memory.
+pub(crate) struct Candidates<'a> {
+    /// The running version
+    crate_version: &'a str,
+    /// Ranks of the prerelease tags used to order versions
+    tags: &'a PrereleaseTags,
+    /// The newest non-yanked stable version seen so far
+    stable: Option<(Vec<String>, VersionInfo)>,
+    /// The newest non-yanked version seen so far, including prereleases
+    any: Option<(Vec<String>, VersionInfo)>,
+    /// The running version's own entry, needed to know if it was yanked
+    running: Option<VersionInfo>,
+}
+
+impl<'a> Candidates<'a> {
+    /// Creates an empty set of candidates for `crate_version`.
+    pub(crate) fn new(crate_version: &'a str, tags: &'a PrereleaseTags) -> Self {
+        Candidates {
+            crate_version,
+            tags,
+            stable: None,
+            any: None,
+            running: None,
         }
     }
 
-    /// Checks if a newer version of a crate is available.
-    ///
-    /// # Arguments
-    ///
-    /// * `crate_name` - The name of the crate to check (e.g., "serde")
-    /// * `crate_version` - The current version you're using (e.g., "1.0.150")
-    ///
-    /// # Returns
-    ///
-    /// * `Some(UpdateResult)` - If a newer version is available
-    /// * `None` - If you're already on the latest version or if the query fails
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// use updates::UpdateChecker;
-    ///
-    /// let checker = UpdateChecker::new(false);
-    ///
-    /// // Check a stable release
-    /// if let Some(update) = checker.check("regex", "1.5.0") {
-    ///     println!("Regex update available: {}", update.available_version);
-    /// }
-    ///
-    /// // Check a prerelease (will also consider other prereleases)
-    /// if let Some(update) = checker.check("tokio", "1.0.0-alpha.1") {
-    ///     println!("Tokio prerelease update: {}", update.available_version);
-    /// }
-    /// ```
-    pub fn check(&self, crate_name: &str, crate_version: &str) -> Option<UpdateResult> {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-
-        let key = (crate_name.to_string(), crate_version.to_string());
+    /// Considers a published version, keeping it if it could be selected.
+    pub(crate) fn offer(&mut self, version: VersionInfo) {
+        if version.num == self.crate_version
+            && self
+                .running
+                .as_ref()
+                .is_none_or(|r| is_preferred_copy(&version, r))
+        {
+            self.running = Some(version.clone());
+        }
 
-        // Check cache
-        if !self.bypass_cache {
-            if let Ok(locked_cache) = self.cache.lock() {
-                if let Some(entry) = locked_cache.get(&key) {
-                    if now - entry.timestamp < CACHE_EXPIRE_TIME {
-                        return entry.result.clone();
-                    }
-                }
-            }
+        if version.yanked {
+            return;
         }
 
-        // Query crates.io
-        let include_prereleases = !standard_release(crate_version);
-        let result = match crates_io(crate_name, include_prereleases) {
-            Ok(data) => {
-                if parse_version(crate_version) >= parse_version(&data.version) {
-                    None
-                } else {
-                    Some(UpdateResult::new(
-                        crate_name.to_string(),
-                        crate_version.to_string(),
-                        data.version,
-                        data.created_at,
-                    ))
-                }
-            }
-            Err(_) => None,
+        // Only strictly newer versions (or preferred copies of the same one)
+        // replace a candidate, so the outcome matches `select_version`
+        let key = parse_version_with(&version.num, self.tags);
+        let replaces = |kept: &Option<(Vec<String>, VersionInfo)>| {
+            kept.as_ref().is_none_or(|(k, v)| {
+                key > *k || (v.num == version.num && is_preferred_copy(&version, v))
+            })
         };
 
-        // Update cache
-        if let Ok(mut locked_cache) = self.cache.lock() {
-            locked_cache.insert(
-                key,
-                CacheEntry {
-                    timestamp: now,
-                    result: result.clone(),
-                },
-            );
+        if self.tags.is_release(&version.num) && replaces(&self.stable) {
+            self.stable = Some((key.clone(), version.clone()));
         }
+        if replaces(&self.any) {
+            self.any = Some((key, version));
+        }
+    }
 
-        self.save_to_permacache();
-        result
+    /// Returns the kept versions.
+    pub(crate) fn into_versions(self) -> Vec<VersionInfo> {
+        [self.any, self.stable]
+            .into_iter()
+            .flatten()
+            .map(|(_, version)| version)
+            .chain(self.running)
+            .collect()
     }
 }
 
-/// Data returned from a successful crates.io query.
-struct CratesIoData {
-    /// The version number
-    version: String,
-    /// When this version was created
-    created_at: Option<String>,
+/// Returns the current Unix timestamp in seconds.
+pub(crate) fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
 }
 
-/// Queries crates.io for the latest version of a crate.
+/// Compares `crate_version` against the newest suitable version in `versions`.
+///
+/// Prereleases are only considered if `crate_version` is itself a prerelease.
 ///
 /// # Arguments
 ///
-/// * `package` - The crate name to query
-/// * `include_prereleases` - Whether to include prerelease versions (alpha, beta, rc, etc.)
+/// * `crate_name` - The name of the crate being checked
+/// * `crate_version` - The current version
+/// * `versions` - The published versions to choose from
+/// * `tags` - Ranks of the prerelease tags used to order versions
+/// * `filter` - Additional predicate a candidate version must satisfy
 ///
 /// # Returns
 ///
-/// * `Ok(CratesIoData)` - The latest version information
-/// * `Err` - If the query fails or no suitable version is found
-fn crates_io(
-    package: &str,
-    include_prereleases: bool,
-) -> Result<CratesIoData, Box<dyn std::error::Error>> {
-    let url = format!("https://crates.io/api/v1/crates/{}", package);
-    let response = reqwest::blocking::Client::new()
-        .get(&url)
-        .header(
-            "User-Agent",
-            &format!(
-                "updates-rs/{} (+{})",
-                env!("CARGO_PKG_VERSION"),
-                env!("CARGO_PKG_REPOSITORY")
-            ),
-        )
-        .timeout(Duration::from_secs(3))
-        .send()?;
+/// * `Ok(Some(UpdateResult))` - If a newer version is available
+/// * `Ok(None)` - If `crate_version` is already the newest
+/// * `Err(UpdateError::InvalidVersion)` - If `crate_version` is invalid
+/// * `Err` - If no suitable version is found
+pub(crate) fn compare_versions(
+    crate_name: &str,
+    crate_version: &str,
+    versions: &[VersionInfo],
+    tags: &PrereleaseTags,
+    filter: impl Fn(&VersionInfo) -> bool,
+) -> Result<Option<UpdateResult>, UpdateError> {
+    validate_version(crate_version, tags)?;
+    let include_prereleases = !tags.is_release(crate_version);
+    let version_info = select_version(versions, include_prereleases, tags, filter)?;
+    let yanked_running = dedup_versions(versions)
+        .into_iter()
+        .find(|v| v.num == crate_version && v.yanked);
 
-    if !response.status().is_success() {
-        return Err(format!("HTTP error: {}", response.status()).into());
+    // A yanked running version is reported even if nothing newer is available
+    if yanked_running.is_none()
+        && parse_version_with(crate_version, tags) >= parse_version_with(&version_info.num, tags)
+    {
+        return Ok(None);
     }
 
-    let data: CratesIoResponse = response.json()?;
+    Ok(Some(UpdateResult::new(
+        crate_name.to_string(),
+        crate_version.to_string(),
+        version_info.num.clone(),
+        version_info.created_at.clone(),
+        yanked_running.is_some(),
+        yanked_running.and_then(|v| v.yank_message.clone()),
+    )))
+}
 
-    // Filter out yanked versions
-    let mut versions: Vec<&VersionInfo> = data.versions.iter().filter(|v| !v.yanked).collect();
+/// Counts the versions in `versions` that are newer than `crate_version`.
+///
+/// # Arguments
+///
+/// * `versions` - The published versions
+/// * `crate_version` - The running version
+/// * `include_yanked` - Whether yanked versions are counted
+/// * `tags` - Ranks of the prerelease tags used to order versions
+///
+/// # Returns
+///
+/// * `Ok(usize)` - The number of newer versions
+/// * `Err(UpdateError::InvalidVersion)` - If `crate_version` is invalid
+pub(crate) fn count_versions_behind(
+    versions: &[VersionInfo],
+    crate_version: &str,
+    include_yanked: bool,
+    tags: &PrereleaseTags,
+) -> Result<usize, UpdateError> {
+    validate_version(crate_version, tags)?;
+    let include_prereleases = !tags.is_release(crate_version);
+    let current = parse_version_with(crate_version, tags);
 
-    if versions.is_empty() {
-        return Err("No non-yanked versions found".into());
-    }
+    Ok(dedup_versions(versions)
+        .into_iter()
+        .filter(|v| include_yanked || !v.yanked)
+        .filter(|v| include_prereleases || tags.is_release(&v.num))
+        .filter(|v| parse_version_with(&v.num, tags) > current)
+        .count())
+}
 
-    // Sort by version (newest first)
-    versions.sort_by(|a, b| parse_version(&b.num).cmp(&parse_version(&a.num)));
+/// Returns the yanked versions in `versions` newer than `current` and no newer
+/// than `latest`, oldest first.
+///
+/// # Arguments
+///
+/// * `versions` - The published versions
+/// * `current` - The lower bound (excluded)
+/// * `latest` - The upper bound (included)
+/// * `tags` - Ranks of the prerelease tags used to order versions
+///
+/// # Returns
+///
+/// * `Ok(Vec<String>)` - The yanked versions in range
+/// * `Err(UpdateError::InvalidVersion)` - If either bound is invalid
+pub(crate) fn yanked_between(
+    versions: &[VersionInfo],
+    current: &str,
+    latest: &str,
+    tags: &PrereleaseTags,
+) -> Result<Vec<String>, UpdateError> {
+    validate_version(current, tags)?;
+    validate_version(latest, tags)?;
+    let current = parse_version_with(current, tags);
+    let latest = parse_version_with(latest, tags);
 
-    // Find the best version based on prerelease preference
-    let version_info = versions
-        .iter()
-        .find(|v| include_prereleases || standard_release(&v.num))
-        .ok_or("No suitable version found")?;
+    let mut yanked: Vec<(Vec<String>, &str)> = dedup_versions(versions)
+        .into_iter()
+        .filter(|v| v.yanked)
+        .map(|v| (parse_version_with(&v.num, tags), v.num.as_str()))
+        .filter(|(key, _)| *key > current && *key <= latest)
+        .collect();
+    yanked.sort();
 
-    Ok(CratesIoData {
-        version: version_info.num.clone(),
-        created_at: Some(version_info.created_at.clone()),
-    })
+    Ok(yanked.into_iter().map(|(_, num)| num.to_string()).collect())
+}
+
+/// Checks if a version's declared MSRV is satisfied by the given Rust version.
+///
+/// Versions that don't declare a `rust-version` are assumed to be compatible.
+///
+/// # Arguments
+///
+/// * `version` - The version whose MSRV to check
+/// * `rustc_version` - The Rust version being built with (e.g., "1.65")
+pub(crate) fn supports_rust_version(version: &VersionInfo, rustc_version: &str) -> bool {
+    version
+        .rust_version
+        .as_deref()
+        .is_none_or(|msrv| parse_version(msrv) <= parse_version(rustc_version))
+}
+
+/// Checks if a version shares the `major.minor` series of `current`.
+///
+/// Versions that aren't valid semver never match.
+///
+/// # Arguments
+///
+/// * `version` - The candidate version
+/// * `current` - The version whose series to match
+pub(crate) fn same_minor_series(version: &VersionInfo, current: &Version) -> bool {
+    Version::parse(&version.num).is_ok_and(|v| v.major == current.major && v.minor == current.minor)
+}
+
+/// Checks that a version string is valid semver.
+///
+/// With post-release semantics enabled in `tags`, post-releases that semver
+/// rejects (e.g. "1.0.0.post1") are accepted too.
+///
+/// # Arguments
+///
+/// * `version` - The version string to validate
+/// * `tags` - The prerelease tags of the crate being checked
+///
+/// # Returns
+///
+/// * `Ok(())` - If the version is valid
+/// * `Err(UpdateError::InvalidVersion)` - If it isn't (e.g., "v1.2" or "latest")
+pub(crate) fn validate_version(version: &str, tags: &PrereleaseTags) -> Result<(), UpdateError> {
+    if Version::parse(version).is_ok() || (!standard_release(version) && tags.is_release(version)) {
+        Ok(())
+    } else {
+        Err(UpdateError::InvalidVersion(version.to_string()))
+    }
 }
 
 /// Checks if a version string represents a standard release (not a prerelease).
@@ -371,6 +3679,39 @@ pub(crate) fn standard_release(version: &str) -> bool {
     version.chars().all(|c| c.is_ascii_digit() || c == '.')
 }
 
+/// Timestamp formats without an offset accepted by [`parse_release_date`].
+/// These are interpreted as UTC.
+const NAIVE_DATE_FORMATS: [&str; 2] = ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"];
+
+/// Parses a release timestamp, tolerating minor format differences between
+/// registries.
+///
+/// RFC3339 (what crates.io returns) is tried first, then RFC2822, then ISO-8601
+/// variants without a timezone (`T` or space separated, optional fractional
+/// seconds), which are assumed to be UTC.
+///
+/// # Arguments
+///
+/// * `date` - The timestamp to parse
+///
+/// # Returns
+///
+/// The parsed timestamp, or `None` if no supported format matches.
+pub(crate) fn parse_release_date(date: &str) -> Option<DateTime<Utc>> {
+    let date = date.trim();
+
+    DateTime::parse_from_rfc3339(date)
+        .or_else(|_| DateTime::parse_from_rfc2822(date))
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            NAIVE_DATE_FORMATS
+                .iter()
+                .find_map(|format| NaiveDateTime::parse_from_str(date, format).ok())
+                .map(|dt| dt.and_utc())
+        })
+}
+
 /// Formats a datetime as a human-readable relative time string.
 ///
 /// # Arguments
@@ -380,8 +3721,10 @@ pub(crate) fn standard_release(version: &str) -> bool {
 /// # Returns
 ///
 /// A human-readable string like "2 hours ago", "3 days ago", or a full date
-/// if more than 7 days in the past.
-fn pretty_date(the_datetime: DateTime<Utc>) -> String {
+/// if more than 7 days in the past. Dates slightly in the future (within
+/// [`CLOCK_SKEW_TOLERANCE`]) are assumed to be caused by clock skew and are
+/// reported as "just now".
+pub(crate) fn pretty_date(the_datetime: DateTime<Utc>) -> String {
     let now = Utc::now();
     let diff = now.signed_duration_since(the_datetime);
 
@@ -390,9 +3733,14 @@ fn pretty_date(the_datetime: DateTime<Utc>) -> String {
         return the_datetime.format("%x %X").to_string();
     }
 
+    // If slightly in the future, the local clock is most likely behind
+    if diff.num_seconds() < 0 && diff.num_seconds() >= -CLOCK_SKEW_TOLERANCE {
+        return "just now".to_string();
+    }
+
     // If negative (future date), use HumanTime for future duration
-    if diff.num_days() < 0 {
-        let future_duration = Duration::from_secs(diff.num_seconds().abs() as u64);
+    if diff.num_seconds() < 0 {
+        let future_duration = Duration::from_secs(diff.num_seconds().unsigned_abs());
         return format!("in {}", HumanTime::from(future_duration).to_string());
     }
 
@@ -431,12 +3779,142 @@ fn pretty_date(the_datetime: DateTime<Utc>) -> String {
 /// updates::check("my-tool", "1.0.0", true);
 /// ```
 pub fn check(crate_name: &str, crate_version: &str, bypass_cache: bool) {
-    let checker = UpdateChecker::new(bypass_cache);
-    if let Some(result) = checker.check(crate_name, crate_version) {
-        eprintln!("{}", result);
+    UpdateChecker::new(bypass_cache).notify(crate_name, crate_version);
+}
+
+/// Guard returned by [`check_at_exit`] that prints the update notice when dropped.
+///
+/// Dropping the guard waits for the background check to finish and prints the
+/// notice to stderr if an update is available. Hold it for the lifetime of
+/// `main` so the notice appears just before your program exits.
+#[must_use = "dropping the guard immediately waits for the check and prints right away"]
+pub struct ExitNotifier {
+    /// Handle to the background check thread
+    handle: Option<JoinHandle<Option<UpdateResult>>>,
+}
+
+impl Drop for ExitNotifier {
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle.take()
+            && let Ok(Some(result)) = handle.join()
+        {
+            eprintln!("{}", result);
+        }
+    }
+}
+
+/// Convenience function that checks for updates in the background and prints
+/// to stderr when the returned guard is dropped.
+///
+/// The check starts immediately on a separate thread, so it doesn't delay
+/// startup. Dropping the returned [`ExitNotifier`] triggers the print, so bind
+/// it to a named variable (not `_`, which drops it immediately) at the top of
+/// `main`, and the notice will appear right before `main` returns.
+///
+/// # Arguments
+///
+/// * `crate_name` - The name of your crate
+/// * `crate_version` - The current version of your crate (typically from `env!("CARGO_PKG_VERSION")`)
+///
+/// # Examples
+///
+/// ```no_run
+/// // At the top of `main`
+/// let _notifier = updates::check_at_exit("my-cli-tool", env!("CARGO_PKG_VERSION"));
+///
+/// // ... rest of your application
+///
+/// // The update notice (if any) is printed when `_notifier` goes out of scope
+/// ```
+pub fn check_at_exit(crate_name: &str, crate_version: &str) -> ExitNotifier {
+    let crate_name = crate_name.to_string();
+    let crate_version = crate_version.to_string();
+    let handle = std::thread::spawn(move || {
+        UpdateChecker::new(false).check_owned(crate_name, crate_version)
+    });
+
+    ExitNotifier {
+        handle: Some(handle),
     }
 }
 
+/// Convenience function that returns the newest stable version of a crate.
+///
+/// # Arguments
+///
+/// * `crate_name` - The name of the crate to look up
+///
+/// # Returns
+///
+/// * `Some(String)` - The newest stable, non-yanked version
+/// * `None` - If the query fails or the crate has no stable release
+///
+/// # Examples
+///
+/// ```no_run
+/// if let Some(version) = updates::latest("serde") {
+///     println!("Latest serde: {}", version);
+/// }
+/// ```
+pub fn latest(crate_name: &str) -> Option<String> {
+    UpdateChecker::default().latest_version(crate_name)
+}
+
+/// The difference between two versions, component by component.
+///
+/// Returned by [`version_diff`]. Each delta is `to - from`, so a component that
+/// was reset by a bigger bump is negative (e.g. `1.4.2 → 2.0.0` has a minor
+/// delta of `-4`). Deltas are `i128` so that any pair of `u64` components
+/// fits without overflowing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VersionDiff {
+    /// Change in the major component
+    pub major: i128,
+    /// Change in the minor component
+    pub minor: i128,
+    /// Change in the patch component
+    pub patch: i128,
+    /// Whether the prerelease identifier differs (e.g. `-beta.1` → `-rc.1`)
+    pub prerelease_changed: bool,
+}
+
+/// Compares two versions component by component.
+///
+/// Useful to describe an update (e.g. "↑ major (1 → 2)") or decide how severe
+/// it is. Build metadata is ignored.
+///
+/// # Arguments
+///
+/// * `from` - The version being moved away from (e.g. the running version)
+/// * `to` - The version being moved to (e.g. the available version)
+///
+/// # Returns
+///
+/// * `Some(VersionDiff)` - The difference between the versions
+/// * `None` - If either version isn't valid semver
+///
+/// # Examples
+///
+/// ```
+/// use updates::version_diff;
+///
+/// let diff = version_diff("1.4.2", "2.0.0").unwrap();
+/// assert_eq!((diff.major, diff.minor, diff.patch), (1, -4, -2));
+/// assert!(!diff.prerelease_changed);
+/// ```
+pub fn version_diff(from: &str, to: &str) -> Option<VersionDiff> {
+    let from = Version::parse(from).ok()?;
+    let to = Version::parse(to).ok()?;
+    let delta = |from: u64, to: u64| i128::from(to) - i128::from(from);
+
+    Some(VersionDiff {
+        major: delta(from.major, to.major),
+        minor: delta(from.minor, to.minor),
+        patch: delta(from.patch, to.patch),
+        prerelease_changed: from.pre != to.pre,
+    })
+}
+
 /// Parses a version string into a comparable format.
 ///
 /// This implements a version comparison algorithm similar to setuptools'
@@ -451,32 +3929,67 @@ pub fn check(crate_name: &str, crate_version: &str, bypass_cache: bool) {
 /// A vector of strings that can be compared lexicographically to determine
 /// version ordering.
 pub(crate) fn parse_version(s: &str) -> Vec<String> {
-    let component_re = Regex::new(r"(\d+|[a-z]+|\.|-)").unwrap();
+    parse_version_with(
+        s,
+        DEFAULT_PRERELEASE_TAGS.get_or_init(PrereleaseTags::default),
+    )
+}
+
+/// Encodes a prerelease tag rank for comparison in [`parse_version_with`].
+///
+/// The built-in ranks encode as the letters their tags have always been
+/// normalised to (`dev` as `@`, `alpha` as `a`, `beta` as `b`, `rc` as `c`),
+/// so ranked tags keep their alphabetical order against unrecognised ones.
+/// Other ranks sort just after the nearest lower built-in rank.
+fn rank_key(rank: u8) -> String {
+    const ANCHORS: [(u8, char); 4] = [(10, '@'), (20, 'a'), (30, 'b'), (40, 'c')];
+
+    match ANCHORS.iter().rev().find(|&&(anchor, _)| anchor <= rank) {
+        Some(&(anchor, letter)) if anchor == rank => letter.to_string(),
+        Some(&(_, letter)) => format!("{}~{:03}", letter, rank),
+        // "!" sorts before "@"
+        None => format!("!{:03}", rank),
+    }
+}
+
+/// Parses a version string into a comparable format, ranking prerelease tags
+/// with `tags`.
+///
+/// See [`parse_version`]. Known prerelease tags are encoded by rank, so they
+/// order relative to each other by rank and always before the final release.
+/// Unknown tags are kept as-is.
+///
+/// # Arguments
+///
+/// * `s` - The version string to parse
+/// * `tags` - Ranks of the prerelease tags to recognise
+pub(crate) fn parse_version_with(s: &str, tags: &PrereleaseTags) -> Vec<String> {
+    let component_re =
+        VERSION_COMPONENT_RE.get_or_init(|| Regex::new(VERSION_COMPONENT_PATTERN).unwrap());
     let s_lower = s.to_lowercase();
     let mut parts = Vec::new();
 
     for part in component_re.find_iter(&s_lower) {
-        let mut part_str = part.as_str().to_string();
-
-        // Apply replacements to normalise prerelease identifiers
-        part_str = match part_str.as_str() {
-            "pre" => "c".to_string(),
-            "preview" => "c".to_string(),
-            "-" => "final-".to_string(),
-            "rc" => "c".to_string(),
-            "dev" => "@".to_string(),
-            "alpha" => "a".to_string(),
-            "beta" => "b".to_string(),
-            _ => part_str,
-        };
+        let part_str = part.as_str();
 
-        if part_str.is_empty() || part_str == "." {
+        if part_str == "." {
             continue;
         }
 
-        if part_str.chars().next().unwrap().is_ascii_digit() {
+        if part_str == "-" {
+            parts.push("*final-".to_string());
+        } else if part_str.starts_with(|c: char| c.is_ascii_digit()) {
             // Pad numbers for proper numerical comparison
             parts.push(format!("{:0>8}", part_str));
+        } else if tags.is_post_release(part_str) {
+            // "~" sorts after letters, so post-releases sort after "*final".
+            // `-post1` and `.post1` are the same post-release.
+            if parts.last().is_some_and(|p| p == "*final-") {
+                parts.pop();
+            }
+            parts.push("*~post".to_string());
+        } else if let Some(rank) = tags.rank(part_str) {
+            parts.push(format!("*{}", rank_key(rank)));
         } else {
             parts.push(format!("*{}", part_str));
         }
@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Errors that can occur while checking for updates.
+///
+/// Most of the crate's API follows an "errors return `None`" philosophy, so
+/// these only surface from the fallible methods that explicitly return them.
+///
+/// New variants may be added in future releases, so matches must include a
+/// wildcard arm.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum UpdateError {
+    /// Cache data could not be serialised or deserialised
+    Cache(String),
+    /// The request to the registry failed or its response could not be read
+    Network(String),
+    /// The registry responded with a non-success HTTP status code
+    Http(u16),
+    /// The crate doesn't exist on the registry
+    NotFound,
+    /// The crate exists, but has no versions or every version has been yanked
+    AllYanked,
+    /// The registry has no version matching the requested criteria
+    NoSuitableVersion,
+    /// The provided version string isn't valid semver
+    InvalidVersion(String),
+    /// Local version data could not be read
+    Io(String),
+    /// The registry's response wasn't the expected JSON
+    Parse {
+        /// The first few hundred bytes of the response body
+        snippet: String,
+    },
+}
+
+impl fmt::Display for UpdateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UpdateError::Cache(msg) => write!(f, "Cache error: {}", msg),
+            UpdateError::Network(msg) => write!(f, "Network error: {}", msg),
+            UpdateError::Http(status) => write!(f, "HTTP error: {}", status),
+            UpdateError::NotFound => write!(f, "Crate not found"),
+            UpdateError::AllYanked => write!(f, "All versions have been yanked"),
+            UpdateError::NoSuitableVersion => write!(f, "No suitable version found"),
+            UpdateError::InvalidVersion(version) => write!(f, "Invalid version: {}", version),
+            UpdateError::Io(msg) => write!(f, "I/O error: {}", msg),
+            UpdateError::Parse { snippet } => {
+                write!(f, "Malformed registry response: {}", snippet)
+            }
+        }
+    }
+}
+
+impl std::error::Error for UpdateError {}
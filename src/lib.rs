@@ -75,24 +75,168 @@
 //!
 //! # Caching Behaviour
 //!
-//! Update checks are cached in your system's temp directory for 1 hour:
+//! Update checks are cached in your system's temp directory for 1 hour (use
+//! [`UpdateChecker::with_store`] to store them elsewhere):
 //!
 //! - **Cache location**: `{temp_dir}/updates_cache.bin`
-//! - **Cache duration**: 3600 seconds (1 hour)
-//! - **Cache format**: Compact binary format using postcard serialisation
+//! - **Cache duration**: 3600 seconds (1 hour), or 300 seconds (5 minutes) for
+//!   crates that don't exist on crates.io and for failed requests
+//! - **Cache format**: Compact binary format using postcard serialisation,
+//!   behind a format version header. Caches written by older releases are
+//!   converted when loaded
+//! - **Expiry**: Fixed by default; use [`UpdateChecker::sliding_cache`] to extend
+//!   entries each time they are read
 //!
 //! The cache is automatically shared across multiple runs of your application,
 //! so users won't be spammed with update checks every time they run your tool.
 //!
 
 mod core;
+mod error;
+mod notify;
+mod source;
+mod store;
 
-pub use core::{UpdateChecker, UpdateResult, check};
+pub use core::{
+    CacheEvent, CheckerConfig, ExitNotifier, Granularity, PublicVersionInfo, UpdateChecker,
+    UpdateResult, VersionDiff, check, check_at_exit, latest, version_diff,
+};
+pub use error::UpdateError;
+pub use notify::{LogNotifier, NoopNotifier, Notifier, StderrNotifier};
+pub use reqwest::StatusCode;
+pub use source::{IndexSource, VersionSnapshot, VersionSource};
+pub use store::{CacheStore, FileCacheStore, MemoryCacheStore};
+
+/// Checks for updates to the crate this macro is invoked from.
+///
+/// A plain function can't know which crate called it: `env!("CARGO_PKG_NAME")`
+/// inside this library would always name `updates` itself. Macros are expanded
+/// in the calling crate, so `CARGO_PKG_NAME` and `CARGO_PKG_VERSION` are read
+/// from *your* package rather than this one. Library authors exposing an update
+/// check should invoke this from the binary crate being checked, not wrap it in
+/// a function of their own, which would capture their crate instead.
+///
+/// Without arguments, this behaves like [`check`] and prints a notice to stderr
+/// if an update is available. Given an [`UpdateChecker`], it runs
+/// [`UpdateChecker::check`] and returns the result instead.
+///
+/// If your binary is published under a different crate name (e.g., binary `rg`
+/// from crate `ripgrep`), pass the published name with `crate_name = ...`. The
+/// version is still taken from `CARGO_PKG_VERSION`.
+///
+/// # Examples
+///
+/// ```no_run
+/// // Checks CARGO_PKG_NAME at CARGO_PKG_VERSION
+/// updates::check_self!();
+///
+/// // Checks "ripgrep" at CARGO_PKG_VERSION
+/// updates::check_self!(crate_name = "ripgrep");
+///
+/// // Returns the result, using a configured checker
+/// let checker = updates::UpdateChecker::new(false);
+/// if let Some(update) = updates::check_self!(checker) {
+///     println!("{}", update.short());
+/// }
+/// ```
+#[macro_export]
+macro_rules! check_self {
+    () => {
+        $crate::check(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"), false)
+    };
+    (crate_name = $name:expr) => {
+        $crate::check($name, env!("CARGO_PKG_VERSION"), false)
+    };
+    ($checker:expr) => {
+        $checker.check(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))
+    };
+}
+
+/// Alias of [`check_self!`], accepting the same arguments.
+///
+/// # Examples
+///
+/// ```no_run
+/// let checker = updates::UpdateChecker::new(false);
+/// if let Some(update) = updates::check_caller!(checker) {
+///     println!("{}", update.short());
+/// }
+/// ```
+#[macro_export]
+macro_rules! check_caller {
+    ($($args:tt)*) => {
+        $crate::check_self!($($args)*)
+    };
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::core::{parse_version, standard_release};
+    use crate::core::{
+        CacheEntry, Candidates, ConnectionLimit, PrereleaseTags, VersionInfo, check_status,
+        compare_versions, count_versions_behind, parse_release_date, parse_response,
+        parse_response_from, parse_version, parse_version_with, pretty_date, same_minor_series,
+        select_version, standard_release, supports_rust_version, unix_now,
+    };
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    fn version(num: &str, yanked: bool, rust_version: Option<&str>) -> VersionInfo {
+        VersionInfo {
+            num: num.to_string(),
+            created_at: Some("2024-01-01T00:00:00Z".to_string()),
+            yanked,
+            yank_message: None,
+            rust_version: rust_version.map(str::to_string),
+        }
+    }
+
+    struct FixtureSource(Vec<PublicVersionInfo>);
+
+    impl VersionSource for FixtureSource {
+        fn versions(&self, _: &str) -> Result<Vec<PublicVersionInfo>, UpdateError> {
+            Ok(self.0.clone())
+        }
+    }
+
+    fn fixture(versions: &[(&str, bool)]) -> FixtureSource {
+        FixtureSource(
+            versions
+                .iter()
+                .map(|&(num, yanked)| PublicVersionInfo {
+                    name: "my-tool".to_string(),
+                    num: num.to_string(),
+                    created_at: None,
+                    yanked,
+                    yank_message: None,
+                    rust_version: None,
+                })
+                .collect(),
+        )
+    }
+
+    fn update_result(running: &str, available: &str) -> UpdateResult {
+        UpdateResult {
+            crate_name: "my-tool".to_string(),
+            running_version: running.to_string(),
+            available_version: available.to_string(),
+            release_date: None,
+            running_yanked: false,
+            yank_reason: None,
+        }
+    }
+
+    fn seed(checker: &UpdateChecker, name: &str, version: &str, timestamp: u64) {
+        checker.cache.lock().unwrap().insert(
+            (name.to_string(), version.to_string()),
+            CacheEntry {
+                timestamp,
+                result: None,
+                error: None,
+            },
+        );
+    }
 
     #[test]
     fn test_standard_release() {
@@ -125,4 +269,1434 @@ mod tests {
         let result = checker.check("reqwest", "0.13.0");
         assert!(result.is_some());
     }
+
+    #[test]
+    fn test_sliding_cache_refreshes_timestamp() {
+        let key = ("updates-sliding-test".to_string(), "1.0.0".to_string());
+        let stale_ts = unix_now() - 600;
+
+        let fixed = UpdateChecker::with_store(false, MemoryCacheStore::default());
+        seed(&fixed, &key.0, &key.1, stale_ts);
+        assert!(fixed.check(&key.0, &key.1).is_none());
+        assert_eq!(fixed.cache.lock().unwrap()[&key].timestamp, stale_ts);
+
+        let sliding =
+            UpdateChecker::with_store(false, MemoryCacheStore::default()).sliding_cache(true);
+        seed(&sliding, &key.0, &key.1, stale_ts);
+        assert!(sliding.check(&key.0, &key.1).is_none());
+        assert!(sliding.cache.lock().unwrap()[&key].timestamp > stale_ts);
+    }
+
+    #[test]
+    fn test_cache_bytes_round_trip() {
+        let source = UpdateChecker::with_store(true, MemoryCacheStore::default());
+        seed(&source, "updates-bytes-test", "1.0.0", unix_now());
+        let bytes = source.dump_cache_bytes();
+
+        let mut checker = UpdateChecker::with_store(true, MemoryCacheStore::default());
+        checker.load_cache_bytes(&bytes).unwrap();
+        assert!(
+            checker
+                .cache
+                .lock()
+                .unwrap()
+                .contains_key(&("updates-bytes-test".to_string(), "1.0.0".to_string()))
+        );
+
+        assert!(matches!(
+            checker.load_cache_bytes(&[0xff, 0xff, 0xff]),
+            Err(UpdateError::Cache(_))
+        ));
+    }
+
+    #[test]
+    fn test_select_version_for_rust_version() {
+        let versions = vec![
+            version("1.0.0", false, None),
+            version("1.1.0", false, Some("1.60")),
+            version("1.2.0", false, Some("1.65.0")),
+            version("1.3.0", false, Some("1.70")),
+            version("1.4.0", true, None),
+        ];
+
+        let selected = select_version(&versions, false, &PrereleaseTags::default(), |v| {
+            supports_rust_version(v, "1.65")
+        })
+        .unwrap();
+        assert_eq!(selected.num, "1.2.0");
+
+        let selected = select_version(&versions, false, &PrereleaseTags::default(), |v| {
+            supports_rust_version(v, "1.56")
+        })
+        .unwrap();
+        assert_eq!(selected.num, "1.0.0");
+    }
+
+    #[test]
+    fn test_check_many_preserves_order() {
+        let checker = UpdateChecker::with_store(false, MemoryCacheStore::default()).concurrency(2);
+        let crates = [
+            ("updates-batch-a", "1.0.0"),
+            ("updates-batch-b", "1.0.0"),
+            ("updates-batch-c", "1.0.0"),
+        ];
+        for (name, version) in crates {
+            seed(&checker, name, version, unix_now());
+        }
+
+        let names: Vec<String> = checker
+            .check_many(&crates)
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(
+            names,
+            ["updates-batch-a", "updates-batch-b", "updates-batch-c"]
+        );
+
+        let mut streamed = Vec::new();
+        checker.check_many_streaming(&crates, |name, _| streamed.push(name));
+        streamed.sort();
+        assert_eq!(streamed, names);
+    }
+
+    #[test]
+    fn test_not_found_entries_expire_sooner() {
+        let now = unix_now();
+        let mut entry = CacheEntry {
+            timestamp: now - 600,
+            result: None,
+            error: None,
+        };
+        assert!(entry.is_fresh(now));
+
+        entry.error = Some(UpdateError::AllYanked);
+        assert!(entry.is_fresh(now));
+
+        for error in [
+            UpdateError::NotFound,
+            UpdateError::Network("timeout".into()),
+        ] {
+            entry.error = Some(error);
+            assert!(!entry.is_fresh(now));
+        }
+
+        entry.timestamp = now - 60;
+        assert!(entry.is_fresh(now));
+    }
+
+    #[test]
+    fn test_app_identity_user_agent() {
+        let checker = UpdateChecker::new(true);
+        assert!(checker.user_agent().starts_with("updates-rs/"));
+
+        let checker = checker.with_app_identity("my-tool", "2.1.0");
+        assert_eq!(checker.user_agent(), "my-tool/2.1.0 (via updates)");
+    }
+
+    #[test]
+    fn test_select_version_patch_only() {
+        let versions = vec![
+            version("1.4.2", false, None),
+            version("1.4.3", false, None),
+            version("1.4.4", true, None),
+            version("1.5.0", false, None),
+            version("2.0.0", false, None),
+        ];
+
+        let current = semver::Version::parse("1.4.2").unwrap();
+        let selected = select_version(&versions, false, &PrereleaseTags::default(), |v| {
+            same_minor_series(v, &current)
+        })
+        .unwrap();
+        assert_eq!(selected.num, "1.4.3");
+    }
+
+    #[test]
+    fn test_pretty_date_clock_skew() {
+        let skewed = chrono::Utc::now() + chrono::Duration::seconds(20);
+        assert_eq!(pretty_date(skewed), "just now");
+
+        let future = chrono::Utc::now() + chrono::Duration::hours(2);
+        assert!(pretty_date(future).starts_with("in "));
+    }
+
+    #[test]
+    fn test_invalid_version_is_rejected() {
+        let versions = [("1.0.0", false), ("1.1.0", true), ("1.2.0", false)];
+        let checker = UpdateChecker::with_store(true, MemoryCacheStore::default())
+            .with_source(fixture(&versions));
+        let since = chrono::DateTime::<chrono::Utc>::UNIX_EPOCH;
+
+        for version in ["v1.2", "latest"] {
+            assert!(checker.check("my-tool", version).is_none());
+            assert!(matches!(
+                checker.try_check("my-tool", version),
+                Err(UpdateError::InvalidVersion(v)) if v == version
+            ));
+            assert!(checker.refresh("my-tool", version).is_none());
+            assert!(checker.peek_fresh("my-tool", version).is_none());
+            assert!(checker.check_versions("my-tool", &[version])[0].1.is_none());
+            assert!(
+                checker
+                    .check_for_rust_version("my-tool", version, "1.70")
+                    .is_none()
+            );
+            assert!(checker.check_since("my-tool", version, since).is_none());
+            assert!(
+                checker
+                    .check_against(version, &fixture(&versions).0)
+                    .is_none()
+            );
+            assert!(checker.versions_behind("my-tool", version).is_none());
+            assert!(
+                checker
+                    .yanked_between("my-tool", version, "1.2.0")
+                    .is_empty()
+            );
+            assert!(
+                checker
+                    .yanked_between("my-tool", "1.0.0", version)
+                    .is_empty()
+            );
+        }
+
+        // Invalid versions are rejected before anything is cached
+        assert!(checker.cache.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_request_observer_skips_cache_hits() {
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&requests);
+        let checker = UpdateChecker::new(false).on_request(move |_, _, _| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+
+        seed(&checker, "updates-observer-test", "1.0.0", unix_now());
+        assert!(checker.check("updates-observer-test", "1.0.0").is_none());
+        assert_eq!(requests.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_missing_created_at() {
+        let mut versions = vec![
+            version("1.0.0", false, None),
+            version("1.2.0", false, None),
+            version("1.1.0", false, None),
+        ];
+        versions[1].created_at = None;
+
+        let result = compare_versions(
+            "my-tool",
+            "1.0.0",
+            &versions,
+            &PrereleaseTags::default(),
+            |_| true,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(result.available_version, "1.2.0");
+        assert!(!result.has_release_date());
+
+        versions[1].created_at = Some("2024-01-01T00:00:00Z".to_string());
+        let result = compare_versions(
+            "my-tool",
+            "1.0.0",
+            &versions,
+            &PrereleaseTags::default(),
+            |_| true,
+        )
+        .unwrap()
+        .unwrap();
+        assert!(result.has_release_date());
+    }
+
+    #[test]
+    fn test_next_check_after() {
+        let checker = UpdateChecker::with_store(false, MemoryCacheStore::default());
+        assert_eq!(checker.next_check_after("my-tool", "1.0.0"), Duration::ZERO);
+
+        seed(&checker, "my-tool", "1.0.0", unix_now() - 600);
+        let wait = checker.next_check_after("my-tool", "1.0.0");
+        assert!(wait <= Duration::from_secs(3000) && wait > Duration::from_secs(2990));
+
+        // A bypassed cache never delays the next query
+        let bypassed = UpdateChecker::with_store(true, MemoryCacheStore::default());
+        seed(&bypassed, "my-tool", "1.0.0", unix_now() - 600);
+        assert_eq!(
+            bypassed.next_check_after("my-tool", "1.0.0"),
+            Duration::ZERO
+        );
+
+        // Nothing is queried until the initial grace period ends
+        let grace = Duration::from_secs(7200);
+        let checker = bypassed
+            .with_initial_grace(grace)
+            .with_source(fixture(&[("1.0.0", false)]));
+        assert_eq!(checker.next_check_after("my-tool", "1.0.0"), grace);
+        checker.check("my-tool", "1.0.0");
+        let wait = checker.next_check_after("my-tool", "1.0.0");
+        assert!(wait <= grace && wait > grace - Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_index_source() {
+        let root = std::env::temp_dir().join(format!("updates-index-{}", std::process::id()));
+        let crate_dir = root.join("my").join("-t");
+        std::fs::create_dir_all(&crate_dir).unwrap();
+        std::fs::write(
+            crate_dir.join("my-tool"),
+            concat!(
+                r#"{"name":"my-tool","vers":"1.0.0","deps":[],"cksum":"","features":{},"yanked":false}"#,
+                "\n",
+                r#"{"name":"my-tool","vers":"1.1.0","deps":[],"cksum":"","features":{},"yanked":false,"rust_version":"1.80"}"#,
+                "\n",
+                r#"{"name":"my-tool","vers":"1.2.0","deps":[],"cksum":"","features":{},"yanked":true}"#,
+                "\n",
+            ),
+        )
+        .unwrap();
+
+        let checker = UpdateChecker::with_store(true, MemoryCacheStore::default())
+            .with_source(IndexSource::new(&root));
+        let update = checker.check("my-tool", "1.0.0").unwrap();
+        assert_eq!(update.available_version, "1.1.0");
+        assert!(!update.has_release_date());
+        assert!(
+            checker
+                .check_for_rust_version("my-tool", "1.0.0", "1.70")
+                .is_none()
+        );
+
+        assert!(matches!(
+            checker.try_check("missing-tool", "1.0.0"),
+            Err(UpdateError::NotFound)
+        ));
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_days_since_release() {
+        let mut result = update_result("1.0.0", "1.1.0");
+        assert_eq!(result.days_since_release(), None);
+
+        result.release_date = Some(chrono::Utc::now() - chrono::Duration::days(10));
+        assert_eq!(result.days_since_release(), Some(10));
+    }
+
+    #[test]
+    fn test_malformed_response_carries_snippet() {
+        let body = format!("<html><body>{}</body></html>", "x".repeat(500));
+
+        match parse_response(body.as_bytes()) {
+            Err(UpdateError::Parse { snippet }) => {
+                assert!(snippet.starts_with("<html><body>"));
+                assert_eq!(snippet.len(), 200);
+            }
+            _ => panic!("expected a parse error"),
+        }
+
+        let versions =
+            parse_response(br#"{"versions":[{"num":"1.0.0","created_at":null,"yanked":false}]}"#)
+                .unwrap();
+        assert_eq!(versions[0].num, "1.0.0");
+        assert!(versions[0].created_at.is_none());
+    }
+
+    #[test]
+    fn test_count_versions_behind() {
+        let versions = vec![
+            version("1.0.0", false, None),
+            version("1.0.1", true, None),
+            version("1.1.0", false, None),
+            version("1.2.0-rc.1", false, None),
+            version("1.2.0", false, None),
+        ];
+
+        let tags = PrereleaseTags::default();
+        assert_eq!(
+            count_versions_behind(&versions, "1.0.0", false, &tags).ok(),
+            Some(2)
+        );
+        assert_eq!(
+            count_versions_behind(&versions, "1.0.0", true, &tags).ok(),
+            Some(3)
+        );
+        assert_eq!(
+            count_versions_behind(&versions, "1.2.0", false, &tags).ok(),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn test_check_versions() {
+        let checker = UpdateChecker::new(true).with_source(fixture(&[
+            ("1.0.0", false),
+            ("1.1.0", false),
+            ("2.0.0-beta.1", false),
+        ]));
+
+        let results = checker.check_versions("my-tool", &["1.0.0", "1.1.0", "2.0.0-alpha.1"]);
+        let available: Vec<(&str, Option<&str>)> = results
+            .iter()
+            .map(|(version, result)| {
+                (
+                    version.as_str(),
+                    result.as_ref().map(|r| r.available_version.as_str()),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            available,
+            [
+                ("1.0.0", Some("1.1.0")),
+                ("1.1.0", None),
+                ("2.0.0-alpha.1", Some("2.0.0-beta.1")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_release_date_formats() {
+        let expected = chrono::DateTime::parse_from_rfc3339("2024-03-05T14:30:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+
+        for date in [
+            "2024-03-05T14:30:00Z",
+            "2024-03-05T14:30:00.000000+00:00",
+            "2024-03-05T16:30:00+02:00",
+            "Tue, 05 Mar 2024 14:30:00 +0000",
+            "2024-03-05T14:30:00",
+            "2024-03-05T14:30:00.000",
+            "2024-03-05 14:30:00",
+            "2024-03-05 14:30:00.000000",
+        ] {
+            assert_eq!(parse_release_date(date), Some(expected), "{}", date);
+        }
+
+        assert_eq!(parse_release_date("yesterday"), None);
+    }
+
+    #[test]
+    fn test_crate_timeout_override() {
+        let checker = UpdateChecker::new(true)
+            .with_timeout(Duration::from_secs(2))
+            .with_crate_timeout("serde", Duration::from_secs(5));
+
+        assert_eq!(checker.timeout_for("serde"), Duration::from_secs(5));
+        assert_eq!(checker.timeout_for("regex"), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_any_outdated() {
+        let checker = UpdateChecker::with_store(true, MemoryCacheStore::default())
+            .with_source(fixture(&[("1.0.0", false), ("1.1.0", false)]));
+
+        assert!(!checker.any_outdated(&[("my-tool", "1.1.0")]));
+        assert!(checker.any_outdated(&[("my-tool", "1.1.0"), ("my-tool", "1.0.0")]));
+    }
+
+    #[test]
+    fn test_memory_cache_store() {
+        let checker = UpdateChecker::new(false).with_cache_store(MemoryCacheStore::default());
+        assert!(checker.cache.lock().unwrap().is_empty());
+
+        seed(&checker, "updates-store-test", "1.0.0", unix_now());
+        let bytes = checker.dump_cache_bytes();
+
+        let store = MemoryCacheStore::default();
+        store.save(&bytes);
+        let checker = UpdateChecker::new(false).with_cache_store(store);
+        assert_eq!(checker.cache.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_all_yanked_vs_not_found() {
+        let versions = vec![version("1.0.0", true, None), version("1.1.0", true, None)];
+        assert!(matches!(
+            select_version(&versions, false, &PrereleaseTags::default(), |_| true),
+            Err(UpdateError::AllYanked)
+        ));
+        assert!(matches!(
+            select_version(&[], false, &PrereleaseTags::default(), |_| true),
+            Err(UpdateError::AllYanked)
+        ));
+
+        let versions = vec![version("1.0.0-rc.1", false, None)];
+        assert!(matches!(
+            select_version(&versions, false, &PrereleaseTags::default(), |_| true),
+            Err(UpdateError::NoSuitableVersion)
+        ));
+
+        assert!(matches!(
+            check_status(StatusCode::NOT_FOUND),
+            Err(UpdateError::NotFound)
+        ));
+        assert!(matches!(
+            check_status(StatusCode::SERVICE_UNAVAILABLE),
+            Err(UpdateError::Http(503))
+        ));
+        assert!(check_status(StatusCode::OK).is_ok());
+    }
+
+    #[test]
+    fn test_refresh_ignores_cached_entry() {
+        let checker = UpdateChecker::new(false)
+            .with_cache_store(MemoryCacheStore::default())
+            .with_source(fixture(&[("1.0.0", false), ("1.1.0", false)]));
+        seed(&checker, "my-tool", "1.0.0", unix_now());
+        seed(&checker, "my-tool", "0.9.0", unix_now());
+
+        // The seeded entries say "no update", so check serves them from the cache
+        assert!(checker.check("my-tool", "1.0.0").is_none());
+
+        let update = checker.refresh("my-tool", "1.0.0").unwrap();
+        assert_eq!(update.available_version, "1.1.0");
+        assert!(checker.check("my-tool", "1.0.0").is_some());
+        assert!(checker.check("my-tool", "0.9.0").is_none());
+    }
+
+    #[test]
+    fn test_cached_entries_snapshot() {
+        let checker = UpdateChecker::new(false).with_cache_store(MemoryCacheStore::default());
+        let now = unix_now();
+        seed(&checker, "updates-snapshot-test", "1.0.0", now);
+
+        let entries = checker.cached_entries();
+        assert_eq!(entries.len(), 1);
+
+        let (name, version, result, cached_at) = &entries[0];
+        assert_eq!(name, "updates-snapshot-test");
+        assert_eq!(version, "1.0.0");
+        assert!(result.is_none());
+        assert_eq!(*cached_at, std::time::UNIX_EPOCH + Duration::from_secs(now));
+    }
+
+    #[test]
+    fn test_custom_prerelease_tag() {
+        // Snapshot, nightly and milestone builds precede the final release
+        let release = parse_version("1.0.0");
+        assert!(parse_version("1.0.0-snapshot") < parse_version("1.0.0-alpha1"));
+        assert!(parse_version("1.0.0-nightly") < parse_version("1.0.0-alpha1"));
+        assert!(parse_version("1.0.0-beta2") < parse_version("1.0.0-milestone1"));
+        assert!(parse_version("1.0.0-milestone1") < parse_version("1.0.0-rc1"));
+        assert!(parse_version("1.0.0-milestone1") < release);
+
+        // Unrecognised tags compare alphabetically against the built-in ones,
+        // and order after the final release if they sort after "final"
+        assert!(parse_version("1.0.0-unstable1") > release);
+        assert!(parse_version("1.0.0-canary") < release);
+        assert!(parse_version("1.0.0-beta") < parse_version("1.0.0-build"));
+        assert!(parse_version("1.0.0-build") < parse_version("1.0.0-rc"));
+        assert!(parse_version("1.0.0-dev") < parse_version("1.0.0-a1"));
+
+        let mut tags = PrereleaseTags::default();
+        tags.insert("unstable", 35);
+
+        let unstable = parse_version_with("1.0.0-unstable1", &tags);
+        let rc = parse_version_with("1.0.0-rc1", &tags);
+        let release = parse_version_with("1.0.0", &tags);
+        assert!(unstable < rc);
+        assert!(rc < release);
+        assert!(parse_version_with("1.0.0-beta2", &tags) < unstable);
+
+        tags.insert("nightly", 5);
+        assert!(
+            parse_version_with("1.0.0-nightly", &tags) < parse_version_with("1.0.0-dev", &tags)
+        );
+    }
+
+    #[test]
+    fn test_major_jump() {
+        assert_eq!(update_result("1.4.0", "4.0.1").major_jump(), 3);
+        assert_eq!(update_result("1.4.0", "2.0.0").major_jump(), 1);
+        assert_eq!(update_result("1.4.0", "1.9.0").major_jump(), 0);
+        assert_eq!(update_result("0.3.0", "0.4.0").major_jump(), 0);
+        assert_eq!(update_result("1.4", "4.0.1").major_jump(), 0);
+    }
+
+    #[test]
+    fn test_log_notifier() {
+        let mut written = Vec::new();
+        let notifier = LogNotifier::new(&mut written);
+        let result = update_result("1.0.0", "1.2.0");
+
+        notifier.notify(&result);
+        notifier.notify(&result);
+
+        assert_eq!(
+            String::from_utf8(written).unwrap(),
+            "update available: my-tool 1.0.0 → 1.2.0\n".repeat(2)
+        );
+    }
+
+    #[test]
+    fn test_running_version_yanked() {
+        let checker = UpdateChecker::new(true)
+            .with_cache_store(MemoryCacheStore::default())
+            .with_source(fixture(&[("1.4.0", false), ("1.5.0", true)]));
+
+        let result = checker.check("my-tool", "1.5.0").unwrap();
+        assert!(result.running_yanked);
+        assert_eq!(result.available_version, "1.4.0");
+        assert_eq!(
+            result.to_string(),
+            "Version 1.5.0 of my-tool has been yanked. No newer version is available."
+        );
+
+        assert!(checker.check("my-tool", "1.4.0").is_none());
+    }
+
+    #[test]
+    fn test_notify_granularity() {
+        let versions = [("1.2.0", false), ("1.2.5", false)];
+
+        let checker = UpdateChecker::new(true)
+            .with_cache_store(MemoryCacheStore::default())
+            .with_source(fixture(&versions));
+        assert!(checker.check("my-tool", "1.2.0").is_some());
+
+        let checker = UpdateChecker::new(true)
+            .with_cache_store(MemoryCacheStore::default())
+            .with_source(fixture(&versions))
+            .notify_granularity(Granularity::Minor);
+        assert!(checker.check("my-tool", "1.2.0").is_none());
+        assert!(checker.check("my-tool", "1.1.9").is_some());
+
+        let checker = UpdateChecker::new(true)
+            .with_cache_store(MemoryCacheStore::default())
+            .with_source(fixture(&versions))
+            .notify_granularity(Granularity::Major);
+        assert!(checker.check("my-tool", "1.1.9").is_none());
+        assert!(checker.check("my-tool", "0.9.0").is_some());
+    }
+
+    #[test]
+    fn test_clones_share_cache() {
+        let checker = UpdateChecker::new(false).with_cache_store(MemoryCacheStore::default());
+        let clone = checker.clone();
+
+        seed(&checker, "updates-clone-test", "1.0.0", unix_now());
+        assert_eq!(clone.cache.lock().unwrap().len(), 1);
+        assert_eq!(clone.dump_cache_bytes(), checker.dump_cache_bytes());
+    }
+
+    #[test]
+    fn test_streamed_candidates_match_full_list() {
+        // A large response, with yanked releases and prereleases interleaved
+        let mut entries = Vec::new();
+        for major in 0..5 {
+            for minor in 0..20 {
+                for patch in 0..10 {
+                    let yanked = (major + minor + patch) % 7 == 0;
+                    entries.push(format!(
+                        r#"{{"num":"{major}.{minor}.{patch}","created_at":null,"yanked":{yanked},"features":{{"default":["std"]}}}}"#
+                    ));
+                    entries.push(format!(
+                        r#"{{"num":"{major}.{minor}.{patch}-rc.1","created_at":null,"yanked":false}}"#
+                    ));
+                }
+            }
+        }
+        entries.push(r#"{"num":"5.0.0-beta.1","created_at":null,"yanked":false}"#.to_string());
+        entries.push(r#"{"num":"4.19.9","created_at":null,"yanked":true}"#.to_string());
+        let body = format!(
+            r#"{{"crate":{{"name":"my-tool"}},"versions":[{}],"keywords":[]}}"#,
+            entries.join(",")
+        );
+
+        let tags = PrereleaseTags::default();
+        let versions = parse_response(body.as_bytes()).unwrap();
+        assert_eq!(versions.len(), 2002);
+
+        for running in [
+            "0.0.1",
+            "2.5.3",
+            "4.19.8",
+            "4.19.9",
+            "5.0.0-alpha.1",
+            "9.0.0",
+        ] {
+            let mut candidates = Candidates::new(running, &tags);
+            parse_response_from(body.as_bytes(), |v| candidates.offer(v)).unwrap();
+            let streamed = candidates.into_versions();
+            assert!(streamed.len() <= 3);
+
+            let expected = compare_versions("my-tool", running, &versions, &tags, |_| true);
+            let actual = compare_versions("my-tool", running, &streamed, &tags, |_| true);
+            assert_eq!(
+                format!("{:?}", actual),
+                format!("{:?}", expected),
+                "running {}",
+                running
+            );
+        }
+    }
+
+    #[test]
+    fn test_outdated_from_cache() {
+        let checker = UpdateChecker::new(false).with_cache_store(MemoryCacheStore::default());
+        let now = unix_now();
+
+        seed(&checker, "updates-up-to-date", "1.0.0", now);
+        for (name, timestamp) in [("updates-fresh", now), ("updates-stale", now - 7200)] {
+            let mut result = update_result("1.0.0", "1.1.0");
+            result.crate_name = name.to_string();
+            checker.cache.lock().unwrap().insert(
+                (name.to_string(), "1.0.0".to_string()),
+                CacheEntry {
+                    timestamp,
+                    result: Some(result),
+                    error: None,
+                },
+            );
+        }
+
+        let names = |results: Vec<UpdateResult>| -> Vec<String> {
+            results.into_iter().map(|r| r.crate_name).collect()
+        };
+        assert_eq!(
+            names(checker.outdated_from_cache(false)),
+            ["updates-fresh", "updates-stale"]
+        );
+        assert_eq!(names(checker.outdated_from_cache(true)), ["updates-fresh"]);
+    }
+
+    #[test]
+    fn test_duplicate_versions() {
+        let tags = PrereleaseTags::default();
+        let mut older = version("1.1.0", false, None);
+        older.created_at = Some("2023-06-01T00:00:00Z".to_string());
+        let versions = vec![
+            version("1.0.0", false, None),
+            version("1.1.0", true, None),
+            version("1.1.0", false, None),
+            older,
+        ];
+
+        // The non-yanked, most recently created copy is chosen
+        let result = compare_versions("my-tool", "1.0.0", &versions, &tags, |_| true)
+            .unwrap()
+            .unwrap();
+        assert_eq!(result.available_version, "1.1.0");
+        assert_eq!(
+            result.release_date,
+            parse_release_date("2024-01-01T00:00:00Z")
+        );
+
+        // A yanked copy doesn't mark the running version as yanked
+        assert!(
+            compare_versions("my-tool", "1.1.0", &versions, &tags, |_| true)
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(
+            count_versions_behind(&versions, "1.0.0", true, &tags).ok(),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_initial_grace() {
+        let checker = UpdateChecker::new(true)
+            .with_cache_store(MemoryCacheStore::default())
+            .with_source(fixture(&[("1.0.0", false), ("1.1.0", false)]))
+            .with_initial_grace(Duration::from_secs(3 * 24 * 60 * 60));
+
+        // The first run only records the marker
+        assert!(checker.check("my-tool", "1.0.0").is_none());
+        assert!(checker.check("my-tool", "1.0.0").is_none());
+        assert!(checker.cached_entries().is_empty());
+
+        // Backdate the first run past the grace period
+        for entry in checker.cache.lock().unwrap().values_mut() {
+            entry.timestamp -= 4 * 24 * 60 * 60;
+        }
+        assert!(checker.check("my-tool", "1.0.0").is_some());
+    }
+
+    #[test]
+    fn test_suggested_command() {
+        assert_eq!(
+            update_result("1.4.0", "1.9.0").suggested_command(),
+            "cargo update -p my-tool"
+        );
+        assert_eq!(
+            update_result("1.4.0", "2.0.0").suggested_command(),
+            "cargo add my-tool@2.0.0"
+        );
+        assert_eq!(
+            update_result("0.3.0", "0.4.0").suggested_command(),
+            "cargo add my-tool@0.4.0"
+        );
+        assert_eq!(
+            update_result("1.4.0", "2.0.0").suggested_install_command(),
+            "cargo install my-tool@2.0.0"
+        );
+    }
+
+    #[test]
+    fn test_check_since() {
+        let released = |num: &str, created_at: &str| PublicVersionInfo {
+            name: "my-tool".to_string(),
+            num: num.to_string(),
+            created_at: Some(created_at.to_string()),
+            yanked: false,
+            yank_message: None,
+            rust_version: None,
+        };
+        let checker = UpdateChecker::new(true)
+            .with_cache_store(MemoryCacheStore::default())
+            .with_source(FixtureSource(vec![
+                released("1.0.0", "2024-01-01T00:00:00Z"),
+                released("1.1.0", "2024-03-01T00:00:00Z"),
+                released("1.2.0", "2024-06-01T00:00:00Z"),
+            ]));
+
+        let since = parse_release_date("2024-03-01T00:00:00Z").unwrap();
+        let result = checker.check_since("my-tool", "1.0.0", since).unwrap();
+        assert_eq!(result.available_version, "1.2.0");
+
+        let since = parse_release_date("2024-07-01T00:00:00Z").unwrap();
+        assert!(checker.check_since("my-tool", "1.0.0", since).is_none());
+    }
+
+    #[test]
+    fn test_config() {
+        let config = UpdateChecker::new(false).config();
+        assert!(!config.bypass_cache);
+        assert_eq!(config.cache_ttl, Duration::from_secs(3600));
+        assert_eq!(config.timeout, Duration::from_secs(3));
+        assert_eq!(config.granularity, Granularity::Patch);
+        assert!(config.registry_url.is_some());
+
+        let config = UpdateChecker::new(true)
+            .concurrency(8)
+            .with_app_identity("my-tool", "1.0.0")
+            .with_crate_timeout("serde", Duration::from_secs(5))
+            .with_max_redirects(2)
+            .notify_granularity(Granularity::Minor)
+            .with_source(fixture(&[]))
+            .config();
+        assert!(config.bypass_cache);
+        assert_eq!(config.concurrency, 8);
+        assert_eq!(config.user_agent, "my-tool/1.0.0 (via updates)");
+        assert_eq!(config.crate_timeouts["serde"], Duration::from_secs(5));
+        assert_eq!(config.max_redirects, 2);
+        assert_eq!(config.granularity, Granularity::Minor);
+        assert_eq!(config.registry_url, None);
+    }
+
+    #[test]
+    fn test_yank_reason() {
+        let body = br#"{"versions":[
+            {"num":"1.5.0","created_at":null,"yanked":true,"yank_message":"critical RCE"},
+            {"num":"1.4.0","created_at":null,"yanked":false,"yank_message":null},
+            {"num":"1.3.0","created_at":null,"yanked":true}
+        ]}"#;
+        let versions = parse_response(body).unwrap();
+        let tags = PrereleaseTags::default();
+
+        let result = compare_versions("my-tool", "1.5.0", &versions, &tags, |_| true)
+            .unwrap()
+            .unwrap();
+        assert_eq!(result.yank_reason.as_deref(), Some("critical RCE"));
+        assert_eq!(
+            result.to_string(),
+            "Version 1.5.0 of my-tool has been yanked: critical RCE. No newer version is available."
+        );
+
+        let result = compare_versions("my-tool", "1.3.0", &versions, &tags, |_| true)
+            .unwrap()
+            .unwrap();
+        assert!(result.running_yanked);
+        assert_eq!(result.yank_reason, None);
+    }
+
+    #[test]
+    fn test_connection_limit() {
+        let limit = ConnectionLimit::new(2);
+        let in_flight = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+
+        std::thread::scope(|scope| {
+            for _ in 0..6 {
+                scope.spawn(|| {
+                    let _permit = limit.acquire();
+                    let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    std::thread::sleep(Duration::from_millis(20));
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+        });
+
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_min_flush_interval() {
+        struct CountingStore(Arc<AtomicUsize>);
+
+        impl CacheStore for CountingStore {
+            fn load(&self) -> Option<Vec<u8>> {
+                None
+            }
+
+            fn save(&self, _data: &[u8]) {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        let saves = Arc::new(AtomicUsize::new(0));
+        let checker = UpdateChecker::new(true)
+            .with_cache_store(CountingStore(saves.clone()))
+            .with_source(fixture(&[("1.0.0", false), ("1.1.0", false)]))
+            .with_min_flush_interval(Duration::from_secs(60));
+
+        // The first change is saved right away, later ones are held back
+        checker.check("my-tool", "1.0.0");
+        checker.check("my-tool", "1.1.0");
+        checker.check("my-tool", "1.0.0");
+        assert_eq!(saves.load(Ordering::SeqCst), 1);
+
+        // Dropping a clone leaves held-back changes to the remaining owner
+        drop(checker.clone());
+        assert_eq!(saves.load(Ordering::SeqCst), 1);
+
+        // Dropping the last owner saves what was held back
+        drop(checker);
+        assert_eq!(saves.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_version_diff() {
+        let diff = version_diff("1.4.2", "1.4.5").unwrap();
+        assert_eq!((diff.major, diff.minor, diff.patch), (0, 0, 3));
+
+        let diff = version_diff("2.0.0-beta.1", "2.0.0-rc.1").unwrap();
+        assert_eq!((diff.major, diff.minor, diff.patch), (0, 0, 0));
+        assert!(diff.prerelease_changed);
+
+        let max = u64::MAX;
+        let diff = version_diff("0.0.0", &format!("{max}.0.{max}")).unwrap();
+        assert_eq!((diff.major, diff.patch), (i128::from(max), i128::from(max)));
+        let diff = version_diff(&format!("{max}.{max}.0"), "0.0.0").unwrap();
+        assert_eq!(
+            (diff.major, diff.minor),
+            (-i128::from(max), -i128::from(max))
+        );
+
+        assert!(version_diff("1.4", "2.0.0").is_none());
+    }
+
+    #[test]
+    fn test_check_self_with_checker() {
+        let checker = UpdateChecker::new(true)
+            .with_cache_store(MemoryCacheStore::default())
+            .with_source(fixture(&[
+                (env!("CARGO_PKG_VERSION"), false),
+                ("99.0.0", false),
+            ]));
+
+        let result = check_self!(checker).unwrap();
+        assert_eq!(result.crate_name, "updates");
+        assert_eq!(result.running_version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(check_caller!(checker), Some(result));
+    }
+
+    #[test]
+    fn test_latest_both() {
+        let checker = UpdateChecker::new(true).with_source(fixture(&[
+            ("1.4.0", false),
+            ("1.5.0-rc.1", false),
+            ("1.6.0-beta.1", true),
+        ]));
+        assert_eq!(
+            checker.latest_both("my-tool"),
+            Some(("1.4.0".to_string(), Some("1.5.0-rc.1".to_string())))
+        );
+
+        let checker = UpdateChecker::new(true)
+            .with_source(fixture(&[("1.4.0-rc.1", false), ("1.4.0", false)]));
+        assert_eq!(
+            checker.latest_both("my-tool"),
+            Some(("1.4.0".to_string(), None))
+        );
+
+        let checker = UpdateChecker::new(true).with_source(fixture(&[("0.1.0-alpha.1", false)]));
+        assert_eq!(checker.latest_both("my-tool"), None);
+
+        let checker = UpdateChecker::new(true).with_source(fixture(&[("1.0.0", true)]));
+        assert_eq!(checker.latest_both("my-tool"), None);
+    }
+
+    #[test]
+    fn test_check_within_budget() {
+        struct SlowSource(FixtureSource, Duration);
+
+        impl VersionSource for SlowSource {
+            fn versions(&self, crate_name: &str) -> Result<Vec<PublicVersionInfo>, UpdateError> {
+                std::thread::sleep(self.1);
+                self.0.versions(crate_name)
+            }
+        }
+
+        let versions = [("1.0.0", false), ("1.1.0", false)];
+        let checker = UpdateChecker::new(false)
+            .with_cache_store(MemoryCacheStore::default())
+            .with_source(SlowSource(fixture(&versions), Duration::from_millis(300)));
+
+        let started = Instant::now();
+        let budget = Duration::from_millis(20);
+        assert!(
+            checker
+                .check_within_budget("my-tool", "1.0.0", budget)
+                .is_none()
+        );
+        assert!(started.elapsed() < Duration::from_millis(250));
+
+        let budget = Duration::from_secs(5);
+        assert!(
+            checker
+                .check_within_budget("my-tool", "1.0.0", budget)
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_update_result_eq_hash() {
+        let mut result = update_result("1.0.0", "1.1.0");
+        result.release_date = parse_release_date("2024-01-01T00:00:00.250Z");
+
+        // Sub-second precision is dropped by the cache, and ignored here
+        let mut cached = result.clone();
+        cached.release_date = parse_release_date("2024-01-01T00:00:00Z");
+        assert_eq!(result, cached);
+
+        let mut other = result.clone();
+        other.running_version = "1.0.1".to_string();
+        assert_ne!(result, other);
+
+        let set: std::collections::HashSet<UpdateResult> = [result, cached, other].into();
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn test_post_releases() {
+        let versions = [
+            ("1.0.0", false),
+            ("1.0.0-post1", false),
+            ("1.0.0-post2", false),
+        ];
+
+        // By default, post-style suffixes are prereleases and never offered to
+        // users of a stable release
+        let checker = UpdateChecker::new(true)
+            .with_cache_store(MemoryCacheStore::default())
+            .with_source(fixture(&versions));
+        assert!(checker.check("my-tool", "1.0.0").is_none());
+
+        let checker = UpdateChecker::new(true)
+            .with_cache_store(MemoryCacheStore::default())
+            .with_source(fixture(&versions))
+            .with_post_releases("my-tool");
+        let result = checker.check("my-tool", "1.0.0").unwrap();
+        assert_eq!(result.available_version, "1.0.0-post2");
+        assert!(checker.check("my-tool", "1.0.0-post2").is_none());
+
+        // The dotted form isn't semver, but is accepted as a running version
+        let result = checker.check("my-tool", "1.0.0.post1").unwrap();
+        assert_eq!(result.available_version, "1.0.0-post2");
+        assert!(
+            checker
+                .try_check("my-tool", "1.0.0.post2")
+                .unwrap()
+                .is_none()
+        );
+        assert!(matches!(
+            checker.try_check("my-tool", "1.0.0.nightly1"),
+            Err(UpdateError::InvalidVersion(_))
+        ));
+
+        let mut tags = PrereleaseTags::default();
+        tags.post_releases = true;
+        let order = [
+            "1.0.0-rc.1",
+            "1.0.0",
+            "1.0.0-post1",
+            "1.0.0.post2",
+            "1.0.0-rev3",
+            "1.0.1",
+        ];
+        for pair in order.windows(2) {
+            assert!(
+                parse_version_with(pair[0], &tags) < parse_version_with(pair[1], &tags),
+                "{} < {}",
+                pair[0],
+                pair[1]
+            );
+        }
+        assert!(tags.is_release("1.0.0.post2"));
+        assert!(!tags.is_release("1.0.0-rc.1"));
+    }
+
+    #[test]
+    fn test_check_with_previous() {
+        let checker = UpdateChecker::new(false)
+            .with_cache_store(MemoryCacheStore::default())
+            .with_source(fixture(&[("1.0.0", false), ("1.2.0", false)]));
+
+        let (current, previous) = checker.check_with_previous("my-tool", "1.0.0");
+        assert_eq!(current.unwrap().available_version, "1.2.0");
+        assert!(previous.is_none());
+
+        // Pretend an older update was cached a while ago
+        if let Some(entry) = checker
+            .cache
+            .lock()
+            .unwrap()
+            .get_mut(&("my-tool".to_string(), "1.0.0".to_string()))
+        {
+            entry.timestamp -= 7200;
+            if let Some(result) = entry.result.as_mut() {
+                result.available_version = "1.1.0".to_string();
+            }
+        }
+
+        let (current, previous) = checker.check_with_previous("my-tool", "1.0.0");
+        assert_eq!(current.unwrap().available_version, "1.2.0");
+        assert_eq!(previous.unwrap().available_version, "1.1.0");
+    }
+
+    #[test]
+    fn test_registry_reachable_with_source() {
+        let checker = UpdateChecker::new(true).with_source(fixture(&[]));
+        assert!(checker.registry_reachable());
+    }
+
+    #[test]
+    fn test_yanked_between() {
+        let checker = UpdateChecker::new(true).with_source(fixture(&[
+            ("1.0.0", true),
+            ("1.0.1", false),
+            ("1.2.0", true),
+            ("1.1.0", true),
+            ("1.3.0", false),
+            ("1.4.0", true),
+            ("1.5.0", true),
+        ]));
+
+        assert_eq!(
+            checker.yanked_between("my-tool", "1.0.0", "1.4.0"),
+            ["1.1.0", "1.2.0", "1.4.0"]
+        );
+        assert!(
+            checker
+                .yanked_between("my-tool", "1.2.0", "1.3.0")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_latest_version_info() {
+        let mut source = fixture(&[("1.0.0", false), ("1.1.0-rc.1", false), ("1.2.0", true)]);
+        source.0[0].rust_version = Some("1.70".to_string());
+        let checker = UpdateChecker::new(true).with_source(source);
+
+        let info = checker.latest_version_info("my-tool", false).unwrap();
+        assert_eq!(
+            (info.name.as_str(), info.num.as_str()),
+            ("my-tool", "1.0.0")
+        );
+        assert!(!info.yanked);
+        assert_eq!(info.rust_version.as_deref(), Some("1.70"));
+
+        let info = checker.latest_version_info("my-tool", true).unwrap();
+        assert_eq!(info.num, "1.1.0-rc.1");
+
+        let checker = UpdateChecker::new(true).with_source(fixture(&[("1.0.0", true)]));
+        assert!(matches!(
+            checker.latest_version_info("my-tool", false),
+            Err(UpdateError::AllYanked)
+        ));
+    }
+
+    #[test]
+    fn test_cache_events() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let record = |events: &Arc<Mutex<Vec<CacheEvent>>>| {
+            let events = Arc::clone(events);
+            move |event: &CacheEvent| events.lock().unwrap().push(*event)
+        };
+
+        // An empty store reports nothing until the first save creates the cache
+        let checker = UpdateChecker::new(false)
+            .with_cache_store(MemoryCacheStore::default())
+            .with_source(fixture(&[("1.0.0", false), ("1.1.0", false)]))
+            .on_cache_event(record(&events));
+        assert!(events.lock().unwrap().is_empty());
+        checker.check("my-tool", "1.0.0");
+        checker.check("my-tool", "1.0.1");
+        assert_eq!(*events.lock().unwrap(), [CacheEvent::Created]);
+
+        // Loading a saved cache is reported when the callback is registered
+        let store = MemoryCacheStore::default();
+        store.save(&checker.dump_cache_bytes());
+        events.lock().unwrap().clear();
+        let _checker = UpdateChecker::new(false)
+            .with_cache_store(store)
+            .on_cache_event(record(&events));
+        assert_eq!(*events.lock().unwrap(), [CacheEvent::Loaded { entries: 2 }]);
+
+        // Unreadable data is discarded, and the next save recreates the cache
+        let store = MemoryCacheStore::default();
+        store.save(&[0xff; 4]);
+        events.lock().unwrap().clear();
+        let checker = UpdateChecker::new(false)
+            .with_cache_store(store)
+            .with_source(fixture(&[("1.0.0", false), ("1.1.0", false)]))
+            .on_cache_event(record(&events));
+        assert!(checker.cached_entries().is_empty());
+        checker.check("my-tool", "1.0.0");
+        assert_eq!(
+            *events.lock().unwrap(),
+            [CacheEvent::Corrupt, CacheEvent::Created]
+        );
+    }
+
+    #[test]
+    fn test_version_snapshot() {
+        let mut source = fixture(&[("1.0.0", false), ("1.1.0", false), ("1.2.0", true)]);
+        source.0[1].rust_version = Some("1.80".to_string());
+        source.0[2].yank_message = Some("broken build".to_string());
+        let snapshot = UpdateChecker::with_store(true, MemoryCacheStore::default())
+            .with_source(source)
+            .snapshot(&["my-tool"])
+            .unwrap();
+        assert!(snapshot.is_fresh(Duration::from_secs(60)));
+
+        let path =
+            std::env::temp_dir().join(format!("updates_snapshot_{}.json", std::process::id()));
+        snapshot.save(&path).unwrap();
+        let loaded = VersionSnapshot::load(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(loaded.fetched_at(), snapshot.fetched_at());
+        assert_eq!(loaded.crate_names().collect::<Vec<_>>(), ["my-tool"]);
+
+        let store = MemoryCacheStore::default();
+        assert!(VersionSnapshot::load_from(&store).is_err());
+        loaded.save_to(&store).unwrap();
+        let loaded = VersionSnapshot::load_from(&store).unwrap();
+
+        let offline =
+            UpdateChecker::with_store(true, MemoryCacheStore::default()).with_source(loaded);
+        assert_eq!(
+            offline.check("my-tool", "1.0.0").unwrap().available_version,
+            "1.1.0"
+        );
+        assert!(
+            offline
+                .check_for_rust_version("my-tool", "1.0.0", "1.70")
+                .is_none()
+        );
+        let yanked = offline.check("my-tool", "1.2.0").unwrap();
+        assert_eq!(yanked.yank_reason.as_deref(), Some("broken build"));
+        assert!(matches!(
+            offline.latest_version_info("other", false),
+            Err(UpdateError::NotFound)
+        ));
+    }
+
+    #[test]
+    fn test_failures_are_cached() {
+        struct CountingSource(Result<FixtureSource, UpdateError>, Arc<AtomicUsize>);
+
+        impl VersionSource for CountingSource {
+            fn versions(&self, crate_name: &str) -> Result<Vec<PublicVersionInfo>, UpdateError> {
+                self.1.fetch_add(1, Ordering::SeqCst);
+                match &self.0 {
+                    Ok(source) => source.versions(crate_name),
+                    Err(e) => Err(e.clone()),
+                }
+            }
+        }
+
+        let checker = |source: Result<FixtureSource, UpdateError>, fetches: &Arc<AtomicUsize>| {
+            UpdateChecker::new(false)
+                .with_cache_store(MemoryCacheStore::default())
+                .with_source(CountingSource(source, Arc::clone(fetches)))
+        };
+
+        let sources = [
+            Ok(fixture(&[("1.0.0", true), ("1.1.0", true)])),
+            Ok(fixture(&[("0.1.0-alpha.1", false)])),
+            Err(UpdateError::Network("timed out".to_string())),
+        ];
+        for source in sources {
+            let fetches = Arc::new(AtomicUsize::new(0));
+            let checker = checker(source, &fetches);
+            for _ in 0..3 {
+                assert!(checker.check("my-tool", "0.0.1").is_none());
+            }
+            assert_eq!(fetches.load(Ordering::SeqCst), 1);
+        }
+
+        // Cached failures are still returned by try_check
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let checker = checker(Ok(fixture(&[("1.0.0", true)])), &fetches);
+        for _ in 0..2 {
+            assert!(matches!(
+                checker.try_check("my-tool", "0.0.1"),
+                Err(UpdateError::AllYanked)
+            ));
+        }
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+
+        // Definitive answers are cached as long as successful checks
+        let now = unix_now();
+        let entries = checker.cache.lock().unwrap();
+        let entry = entries.values().next().unwrap();
+        assert!(entry.remaining(now) > 300);
+    }
+
+    #[test]
+    fn test_with_cache_store_detaches_clones() {
+        let checker = UpdateChecker::new(false)
+            .with_cache_store(MemoryCacheStore::default())
+            .with_source(fixture(&[("1.0.0", false), ("1.1.0", false)]));
+        checker.check("my-tool", "1.0.0");
+
+        let other = checker
+            .clone()
+            .with_cache_store(MemoryCacheStore::default());
+        assert_eq!(checker.cached_entries().len(), 1);
+        assert!(other.cached_entries().is_empty());
+
+        other.check("my-tool", "1.0.1");
+        assert_eq!(checker.cached_entries().len(), 1);
+        assert_eq!(other.cached_entries().len(), 1);
+    }
+
+    #[test]
+    fn test_with_store_loads_given_store() {
+        let seeded = UpdateChecker::with_store(false, MemoryCacheStore::default());
+        seed(&seeded, "my-tool", "1.0.0", unix_now());
+
+        let store = MemoryCacheStore::default();
+        store.save(&seeded.dump_cache_bytes());
+        let checker = UpdateChecker::with_store(false, store);
+        assert_eq!(checker.cached_entries().len(), 1);
+    }
+
+    #[test]
+    fn test_legacy_cache_is_migrated() {
+        // The cache layout written by 0.1.2, before the format was versioned
+        #[derive(serde::Serialize)]
+        struct OldEntry {
+            timestamp: u64,
+            result: Option<OldResult>,
+        }
+
+        #[derive(serde::Serialize)]
+        struct OldResult {
+            crate_name: String,
+            running_version: String,
+            available_version: String,
+            #[serde(with = "chrono::serde::ts_seconds_option")]
+            release_date: Option<chrono::DateTime<chrono::Utc>>,
+        }
+
+        let now = unix_now();
+        let old = std::collections::HashMap::from([(
+            ("my-tool".to_string(), "1.0.0".to_string()),
+            OldEntry {
+                timestamp: now,
+                result: Some(OldResult {
+                    crate_name: "my-tool".to_string(),
+                    running_version: "1.0.0".to_string(),
+                    available_version: "1.1.0".to_string(),
+                    release_date: None,
+                }),
+            },
+        )]);
+        let store = MemoryCacheStore::default();
+        store.save(&postcard::to_allocvec(&old).unwrap());
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&events);
+        let checker = UpdateChecker::with_store(false, store)
+            .on_cache_event(move |event| recorded.lock().unwrap().push(*event));
+        assert_eq!(
+            *events.lock().unwrap(),
+            [CacheEvent::Migrated, CacheEvent::Loaded { entries: 1 }]
+        );
+        let update = checker.check("my-tool", "1.0.0").unwrap();
+        assert_eq!(update.available_version, "1.1.0");
+
+        // The current format round-trips, and unknown versions are rejected
+        let mut bytes = checker.dump_cache_bytes();
+        let mut copy = UpdateChecker::with_store(true, MemoryCacheStore::default());
+        copy.load_cache_bytes(&bytes).unwrap();
+        assert_eq!(copy.cached_entries().len(), 1);
+
+        bytes[4] = u8::MAX;
+        assert!(matches!(
+            copy.load_cache_bytes(&bytes),
+            Err(UpdateError::Cache(_))
+        ));
+    }
+
+    #[test]
+    fn test_unknown_cached_error_is_tolerated() {
+        // A cache entry holding an error variant from a newer release
+        #[derive(serde::Serialize)]
+        struct FutureEntry {
+            timestamp: u64,
+            result: Option<UpdateResult>,
+            error: Option<Vec<u8>>,
+        }
+
+        let entries = std::collections::HashMap::from([(
+            ("my-tool".to_string(), "1.0.0".to_string()),
+            FutureEntry {
+                timestamp: unix_now(),
+                result: None,
+                error: Some(vec![200, 1, 3, b'n', b'e', b'w']),
+            },
+        )]);
+        let empty = UpdateChecker::with_store(true, MemoryCacheStore::default()).dump_cache_bytes();
+        let mut bytes = empty[..5].to_vec();
+        bytes.extend(postcard::to_allocvec(&entries).unwrap());
+
+        let mut checker = UpdateChecker::with_store(false, MemoryCacheStore::default())
+            .with_source(fixture(&[("1.0.0", false), ("1.1.0", false)]));
+        checker.load_cache_bytes(&bytes).unwrap();
+        assert!(matches!(
+            checker.try_check("my-tool", "1.0.0"),
+            Err(UpdateError::Cache(_))
+        ));
+    }
+
+    #[test]
+    fn test_cache_event_callback_can_use_checker() {
+        let checker = UpdateChecker::with_store(false, MemoryCacheStore::default())
+            .with_source(fixture(&[("1.0.0", false), ("1.1.0", false)]));
+        let probe = checker.clone();
+        let seen = Arc::new(AtomicUsize::new(0));
+        let recorded = Arc::clone(&seen);
+        let checker = checker.on_cache_event(move |_| {
+            recorded.store(probe.cached_entries().len(), Ordering::SeqCst);
+        });
+
+        checker.check("my-tool", "1.0.0");
+        assert_eq!(seen.load(Ordering::SeqCst), 1);
+    }
 }
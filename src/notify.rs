@@ -0,0 +1,84 @@
+use crate::core::UpdateResult;
+use std::io::Write;
+use std::sync::Mutex;
+
+/// A destination for update notices.
+///
+/// [`UpdateChecker::notify`](crate::UpdateChecker::notify) hands every available
+/// update to the checker's notifier, which prints to stderr by default. Implement
+/// this trait and pass it to
+/// [`UpdateChecker::with_notifier`](crate::UpdateChecker::with_notifier) to route
+/// notices elsewhere, such as a desktop notification or a status file.
+pub trait Notifier: Send + Sync {
+    /// Reports an available update.
+    ///
+    /// # Arguments
+    ///
+    /// * `result` - The available update
+    fn notify(&self, result: &UpdateResult);
+}
+
+/// A [`Notifier`] that prints the full update notice to stderr.
+///
+/// This is the default notifier, and matches the output of [`check`](crate::check).
+#[derive(Debug, Default, Clone, Copy)]
+pub struct StderrNotifier;
+
+impl Notifier for StderrNotifier {
+    fn notify(&self, result: &UpdateResult) {
+        eprintln!("{}", result);
+    }
+}
+
+/// A [`Notifier`] that writes a compact line per update to a writer.
+///
+/// Each notice is written as a single line in the [`short`](UpdateResult::short)
+/// format, e.g. `update available: serde 1.0.150 → 1.0.200`, which suits log
+/// files. Write errors are ignored.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::fs::OpenOptions;
+/// use updates::{LogNotifier, UpdateChecker};
+///
+/// let log = OpenOptions::new().create(true).append(true).open("my-tool.log").unwrap();
+/// let checker = UpdateChecker::new(false).with_notifier(LogNotifier::new(log));
+/// ```
+pub struct LogNotifier<W> {
+    /// Where notices are written
+    writer: Mutex<W>,
+}
+
+impl<W: Write + Send> LogNotifier<W> {
+    /// Creates a new LogNotifier writing to the given writer.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - Where notices are written
+    pub fn new(writer: W) -> Self {
+        LogNotifier {
+            writer: Mutex::new(writer),
+        }
+    }
+}
+
+impl<W: Write + Send> Notifier for LogNotifier<W> {
+    fn notify(&self, result: &UpdateResult) {
+        if let Ok(mut writer) = self.writer.lock() {
+            let _ = writeln!(writer, "update available: {}", result.short());
+            let _ = writer.flush();
+        }
+    }
+}
+
+/// A [`Notifier`] that discards every notice.
+///
+/// Useful to run checks for their side effects (such as warming the cache)
+/// without reporting anything.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopNotifier;
+
+impl Notifier for NoopNotifier {
+    fn notify(&self, _result: &UpdateResult) {}
+}
@@ -0,0 +1,280 @@
+use crate::core::PublicVersionInfo;
+use crate::error::UpdateError;
+use crate::store::CacheStore;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// A source of published crate versions.
+///
+/// By default, [`UpdateChecker`](crate::UpdateChecker) fetches versions from the
+/// crates.io API. Implement this trait and pass it to
+/// [`UpdateChecker::with_source`](crate::UpdateChecker::with_source) to answer
+/// checks from somewhere else instead, such as a local index for air-gapped
+/// audits. The selection and comparison logic is the same for every source.
+pub trait VersionSource: Send + Sync {
+    /// Returns every published version of a crate, including yanked ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `crate_name` - The name of the crate to look up
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<PublicVersionInfo>)` - The crate's versions
+    /// * `Err(UpdateError::NotFound)` - If the source doesn't know the crate
+    /// * `Err(UpdateError)` - If the source couldn't be read
+    fn versions(&self, crate_name: &str) -> Result<Vec<PublicVersionInfo>, UpdateError>;
+}
+
+/// A single line of a crate's file in the registry index.
+#[derive(Deserialize)]
+struct IndexEntry {
+    /// Name of the crate
+    name: String,
+    /// Version number string (e.g., "1.0.0")
+    vers: String,
+    /// Whether this version has been yanked
+    yanked: bool,
+    /// Minimum supported Rust version declared by this version, if any
+    #[serde(default)]
+    rust_version: Option<String>,
+}
+
+/// A [`VersionSource`] backed by a local copy of the crates.io index.
+///
+/// The directory must use the registry index layout, as found in a checkout of
+/// the [crates.io-index](https://github.com/rust-lang/crates.io-index)
+/// repository: one file per crate containing a JSON object per version. The
+/// index doesn't record publish dates, so results from this source have no
+/// `release_date`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use updates::{IndexSource, UpdateChecker};
+///
+/// let checker = UpdateChecker::new(true).with_source(IndexSource::new("/srv/crates.io-index"));
+///
+/// if let Some(update) = checker.check("serde", "1.0.150") {
+///     println!("{}", update);
+/// }
+/// ```
+pub struct IndexSource {
+    /// Root directory of the index checkout
+    root: PathBuf,
+}
+
+impl IndexSource {
+    /// Creates a new IndexSource reading from the given index directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - Root directory of the index checkout
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        IndexSource { root: root.into() }
+    }
+
+    /// Returns the path of a crate's file within the index.
+    ///
+    /// Crate names are lowercased and bucketed by length, following the
+    /// registry index layout (`1/a`, `2/ab`, `3/a/abc`, `ab/cd/abcd...`).
+    fn crate_path(&self, crate_name: &str) -> PathBuf {
+        let name = crate_name.to_lowercase();
+
+        match name.len() {
+            1 => self.root.join("1").join(&name),
+            2 => self.root.join("2").join(&name),
+            3 => self.root.join("3").join(&name[..1]).join(&name),
+            _ => self.root.join(&name[..2]).join(&name[2..4]).join(&name),
+        }
+    }
+}
+
+impl VersionSource for IndexSource {
+    fn versions(&self, crate_name: &str) -> Result<Vec<PublicVersionInfo>, UpdateError> {
+        if crate_name.is_empty() || !crate_name.is_ascii() {
+            return Err(UpdateError::NotFound);
+        }
+
+        let data = fs::read_to_string(self.crate_path(crate_name)).map_err(|e| match e.kind() {
+            ErrorKind::NotFound => UpdateError::NotFound,
+            _ => UpdateError::Io(e.to_string()),
+        })?;
+
+        data.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                let entry: IndexEntry = serde_json::from_str(line)
+                    .map_err(|e| UpdateError::Io(format!("Malformed index entry: {}", e)))?;
+
+                Ok(PublicVersionInfo {
+                    name: entry.name,
+                    num: entry.vers,
+                    created_at: None,
+                    yanked: entry.yanked,
+                    yank_message: None,
+                    rust_version: entry.rust_version,
+                })
+            })
+            .collect()
+    }
+}
+
+/// A [`VersionSource`] backed by version lists fetched once and saved to a file.
+///
+/// Take a snapshot with [`UpdateChecker::snapshot`](crate::UpdateChecker::snapshot)
+/// and [`save`](Self::save) it to a file (or to any [`CacheStore`] with
+/// [`save_to`](Self::save_to)), then [`load`](Self::load) it in other processes
+/// to check against the same data offline. This suits CI pipelines where a setup
+/// job fetches version lists once and parallel jobs reuse them. The snapshot
+/// records when it was fetched, so consumers can decide whether it's recent
+/// enough. Crates missing from the snapshot are reported as
+/// [`UpdateError::NotFound`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use updates::{UpdateChecker, VersionSnapshot};
+///
+/// // In the setup job
+/// let snapshot = UpdateChecker::new(true).snapshot(&["serde", "regex"]).unwrap();
+/// snapshot.save("versions.json").unwrap();
+///
+/// // In each matrix job
+/// let snapshot = VersionSnapshot::load("versions.json").unwrap();
+/// assert!(snapshot.is_fresh(Duration::from_secs(6 * 60 * 60)));
+///
+/// let checker = UpdateChecker::new(true).with_source(snapshot);
+/// if let Some(update) = checker.check("serde", "1.0.150") {
+///     println!("{}", update);
+/// }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionSnapshot {
+    /// When the version lists were fetched
+    fetched_at: DateTime<Utc>,
+    /// Every published version of each crate, keyed by crate name
+    crates: HashMap<String, Vec<PublicVersionInfo>>,
+}
+
+impl VersionSnapshot {
+    /// Creates a snapshot of version lists fetched at the given time.
+    pub(crate) fn new(
+        fetched_at: DateTime<Utc>,
+        crates: HashMap<String, Vec<PublicVersionInfo>>,
+    ) -> Self {
+        VersionSnapshot { fetched_at, crates }
+    }
+
+    /// Reads a snapshot from a file written by [`save`](Self::save).
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the snapshot file
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(VersionSnapshot)` - The loaded snapshot
+    /// * `Err(UpdateError::Io)` - If the file can't be read or isn't a snapshot
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, UpdateError> {
+        let data = fs::read(path).map_err(|e| UpdateError::Io(e.to_string()))?;
+        Self::from_bytes(&data)
+    }
+
+    /// Writes the snapshot to a file as JSON, replacing any existing file.
+    ///
+    /// Unlike [`save_to`](Self::save_to) with a
+    /// [`FileCacheStore`](crate::FileCacheStore), write failures are reported,
+    /// so a setup job can fail loudly instead of leaving later jobs without data.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the snapshot file
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the snapshot was written
+    /// * `Err(UpdateError::Io)` - If the file can't be written
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), UpdateError> {
+        fs::write(path, self.to_bytes()?).map_err(|e| UpdateError::Io(e.to_string()))
+    }
+
+    /// Reads a snapshot from a [`CacheStore`] written by [`save_to`](Self::save_to).
+    ///
+    /// # Arguments
+    ///
+    /// * `store` - The store holding the snapshot
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(VersionSnapshot)` - The loaded snapshot
+    /// * `Err(UpdateError::Io)` - If the store is empty or doesn't hold a snapshot
+    pub fn load_from(store: &dyn CacheStore) -> Result<Self, UpdateError> {
+        let data = store
+            .load()
+            .ok_or_else(|| UpdateError::Io("No version snapshot saved".to_string()))?;
+        Self::from_bytes(&data)
+    }
+
+    /// Saves the snapshot to a [`CacheStore`], replacing anything saved before.
+    ///
+    /// # Arguments
+    ///
+    /// * `store` - Where to save the snapshot
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the snapshot was handed to the store
+    /// * `Err(UpdateError::Io)` - If the snapshot couldn't be serialised
+    pub fn save_to(&self, store: &dyn CacheStore) -> Result<(), UpdateError> {
+        store.save(&self.to_bytes()?);
+        Ok(())
+    }
+
+    /// Parses a snapshot from its JSON form.
+    fn from_bytes(data: &[u8]) -> Result<Self, UpdateError> {
+        serde_json::from_slice(data)
+            .map_err(|e| UpdateError::Io(format!("Malformed version snapshot: {}", e)))
+    }
+
+    /// Serialises the snapshot to JSON.
+    fn to_bytes(&self) -> Result<Vec<u8>, UpdateError> {
+        serde_json::to_vec(self).map_err(|e| UpdateError::Io(e.to_string()))
+    }
+
+    /// Returns when the version lists were fetched.
+    pub fn fetched_at(&self) -> DateTime<Utc> {
+        self.fetched_at
+    }
+
+    /// Returns `true` if the snapshot was fetched no more than `max_age` ago.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_age` - The oldest snapshot still considered fresh
+    pub fn is_fresh(&self, max_age: Duration) -> bool {
+        // A snapshot from the future (clock skew between jobs) counts as fresh
+        let age = (Utc::now() - self.fetched_at).to_std().unwrap_or_default();
+        age <= max_age
+    }
+
+    /// Returns the names of the crates in the snapshot.
+    pub fn crate_names(&self) -> impl Iterator<Item = &str> {
+        self.crates.keys().map(String::as_str)
+    }
+}
+
+impl VersionSource for VersionSnapshot {
+    fn versions(&self, crate_name: &str) -> Result<Vec<PublicVersionInfo>, UpdateError> {
+        self.crates
+            .get(crate_name)
+            .cloned()
+            .ok_or(UpdateError::NotFound)
+    }
+}
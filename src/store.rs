@@ -0,0 +1,97 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Persistent storage for the update cache.
+///
+/// [`UpdateChecker`](crate::UpdateChecker) keeps its cache in memory and uses a
+/// `CacheStore` to persist it between runs. The default store writes a file in
+/// the system's temp directory. Implement this trait and pass it to
+/// [`UpdateChecker::with_store`](crate::UpdateChecker::with_store) or
+/// [`UpdateChecker::with_cache_store`](crate::UpdateChecker::with_cache_store)
+/// to keep the cache somewhere else, such as your application's config
+/// directory. On platforms without a usable temp directory, use `with_store`,
+/// which never touches the default cache file.
+///
+/// The data is opaque to stores: they only need to hand back the last bytes
+/// they were given.
+pub trait CacheStore: Send + Sync {
+    /// Returns the previously saved cache data, or `None` if there is none.
+    fn load(&self) -> Option<Vec<u8>>;
+
+    /// Persists the cache data, replacing anything saved before.
+    fn save(&self, data: &[u8]);
+}
+
+/// A [`CacheStore`] that keeps the cache in a file.
+///
+/// # Examples
+///
+/// ```no_run
+/// use updates::{FileCacheStore, UpdateChecker};
+///
+/// let checker = UpdateChecker::new(false)
+///     .with_cache_store(FileCacheStore::new("/home/me/.config/my-tool/updates.bin"));
+/// ```
+pub struct FileCacheStore {
+    /// Path to the cache file
+    path: PathBuf,
+}
+
+impl FileCacheStore {
+    /// Creates a new FileCacheStore backed by the given file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the cache file. It is created on the first save.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        FileCacheStore { path: path.into() }
+    }
+}
+
+impl Default for FileCacheStore {
+    /// Creates a FileCacheStore at `{temp_dir}/updates_cache.bin`.
+    fn default() -> Self {
+        FileCacheStore::new(std::env::temp_dir().join("updates_cache.bin"))
+    }
+}
+
+impl CacheStore for FileCacheStore {
+    fn load(&self) -> Option<Vec<u8>> {
+        fs::read(&self.path).ok()
+    }
+
+    fn save(&self, data: &[u8]) {
+        let _ = fs::write(&self.path, data);
+    }
+}
+
+/// A [`CacheStore`] that keeps the cache in memory only.
+///
+/// Nothing is persisted between runs. Useful for tests, or when results should
+/// only be cached for the lifetime of the process.
+///
+/// # Examples
+///
+/// ```
+/// use updates::{MemoryCacheStore, UpdateChecker};
+///
+/// let checker = UpdateChecker::with_store(false, MemoryCacheStore::default());
+/// ```
+#[derive(Default)]
+pub struct MemoryCacheStore {
+    /// The last saved cache data
+    data: Mutex<Option<Vec<u8>>>,
+}
+
+impl CacheStore for MemoryCacheStore {
+    fn load(&self) -> Option<Vec<u8>> {
+        self.data.lock().ok()?.clone()
+    }
+
+    fn save(&self, data: &[u8]) {
+        if let Ok(mut stored) = self.data.lock() {
+            *stored = Some(data.to_vec());
+        }
+    }
+}